@@ -0,0 +1,438 @@
+//! Music and sound effects.
+//!
+//! Scenes talk to an [`AudioManager`] kept in `GameData`: they say which track should be
+//! playing and fire off sound effects by name, and the manager takes care of switching
+//! streams, volumes and missing files. Tracks can be split into stems that all play in sync;
+//! the game sets a [`LayerMix`] from what's happening and the manager fades the stems to it.
+//!
+//! The actual noise comes from an [`AudioBackend`] - raylib's audio device in the game, or
+//! [`NullAudio`] when there's no sound card (or no window, like in tests), which just keeps
+//! track of what would have played.
+use std::collections::HashMap;
+
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const MENU_MUSIC: &str = "./resources/music/menu.wav";
+/// The final duel gets its own track instead of the stage's climb music.
+pub const ROOFTOP_MUSIC: &str = "./resources/final_stage.ogg";
+pub const SFX_DIR: &str = "./resources/sfx";
+
+// how fast stems fade towards their target level, in full volume per second
+const STEM_FADE_RATE: f32 = 0.5;
+
+/// Every sound effect in the bank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Cast,
+    Hit,
+    Jump,
+    Land,
+    Sizzle,
+    Victory,
+    /// Hit of drama as the final duel starts.
+    Stinger,
+}
+
+impl Sfx {
+    pub const ALL: [Sfx; 7] = [Sfx::Cast, Sfx::Hit, Sfx::Jump, Sfx::Land, Sfx::Sizzle, Sfx::Victory, Sfx::Stinger];
+
+    /// File name inside [`SFX_DIR`].
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Sfx::Cast => "cast.wav",
+            Sfx::Hit => "hit.wav",
+            Sfx::Jump => "jump.wav",
+            Sfx::Land => "land.wav",
+            Sfx::Sizzle => "sizzle.wav",
+            Sfx::Victory => "victory.wav",
+            Sfx::Stinger => "stinger.wav",
+        }
+    }
+}
+
+/// Extra stems layered over a track's base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Comes in as the climb speeds up.
+    Drive,
+    /// Comes in near the top of the tower.
+    Peak,
+    /// Comes in while someone is one hit from death.
+    Tension,
+}
+
+/// A piece of music split into stems that start together and stay in sync.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveTrack {
+    /// Always audible.
+    pub base: &'static str,
+    pub layers: Vec<(Layer, &'static str)>,
+}
+
+impl AdaptiveTrack {
+    /// A plain track with no layers.
+    pub fn single(path: &'static str) -> Self {
+        Self { base: path, layers: Vec::new() }
+    }
+
+    fn paths(&self) -> Vec<&'static str> {
+        std::iter::once(self.base).chain(self.layers.iter().map(|(_, p)| *p)).collect()
+    }
+}
+
+/// Target level (0 to 1) for each layer.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LayerMix {
+    pub drive: f32,
+    pub peak: f32,
+    pub tension: f32,
+}
+
+impl LayerMix {
+    pub fn get(&self, layer: Layer) -> f32 {
+        match layer {
+            Layer::Drive => self.drive,
+            Layer::Peak => self.peak,
+            Layer::Tension => self.tension,
+        }
+    }
+}
+
+/// Volume knobs. The effective volume of anything is master times its own category.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeCategory {
+    Master,
+    Music,
+    Sfx,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Volumes {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Self { master: 1.0, music: 0.7, sfx: 0.8 }
+    }
+}
+
+impl Volumes {
+    pub fn get(&self, category: VolumeCategory) -> f32 {
+        match category {
+            VolumeCategory::Master => self.master,
+            VolumeCategory::Music => self.music,
+            VolumeCategory::Sfx => self.sfx,
+        }
+    }
+
+    pub fn set(&mut self, category: VolumeCategory, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match category {
+            VolumeCategory::Master => self.master = volume,
+            VolumeCategory::Music => self.music = volume,
+            VolumeCategory::Sfx => self.sfx = volume,
+        }
+    }
+}
+
+/// Whatever actually makes the noise. Volumes passed in are final, already multiplied out.
+pub trait AudioBackend {
+    /// Starts streaming every stem from the top at once, silent until given a volume,
+    /// replacing whatever was playing. Stems that fail to load stay silent.
+    fn play_music(&mut self, stems: &[&str]);
+    fn stop_music(&mut self);
+    /// `stem` indexes the list given to `play_music`.
+    fn set_stem_volume(&mut self, stem: usize, volume: f32);
+    /// Keeps the music streams fed. Called once a frame.
+    fn update(&mut self);
+    fn play_sound(&mut self, sfx: Sfx, volume: f32);
+}
+
+/// Silent backend. Remembers what it was asked to play so tests can check it.
+#[derive(Debug, Default)]
+pub struct NullAudio {
+    pub stems: Vec<String>,
+    pub stem_volumes: Vec<f32>,
+    /// How many times music was (re)started.
+    pub music_starts: usize,
+    /// Each sound effect with the volume it was played at.
+    pub played: Vec<(Sfx, f32)>,
+}
+
+impl AudioBackend for NullAudio {
+    fn play_music(&mut self, stems: &[&str]) {
+        self.stems = stems.iter().map(|s| s.to_string()).collect();
+        self.stem_volumes = vec![0.0; stems.len()];
+        self.music_starts += 1;
+    }
+
+    fn stop_music(&mut self) {
+        self.stems.clear();
+        self.stem_volumes.clear();
+    }
+
+    fn set_stem_volume(&mut self, stem: usize, volume: f32) {
+        if let Some(v) = self.stem_volumes.get_mut(stem) { *v = volume; }
+    }
+
+    fn update(&mut self) {}
+
+    fn play_sound(&mut self, sfx: Sfx, volume: f32) {
+        self.played.push((sfx, volume));
+    }
+}
+
+/// raylib's audio device. Sounds that fail to load are skipped with a warning, so a missing
+/// file just means silence.
+pub struct RaylibAudioBackend {
+    device: &'static RaylibAudio,
+    stems: Vec<Option<Music<'static>>>,
+    sounds: HashMap<Sfx, Sound<'static>>,
+}
+
+impl RaylibAudioBackend {
+    /// Opens the audio device and loads the sound bank, or `None` if there's no device.
+    pub fn init() -> Option<Self> {
+        let device = match RaylibAudio::init_audio_device() {
+            Ok(device) => device,
+            Err(e) => {
+                tracing::warn!("no audio device ({e}), running silent");
+                return None;
+            }
+        };
+        // the device lives as long as the game, and music and sounds borrow it, so it's
+        // simplest to never let it go
+        let device: &'static RaylibAudio = Box::leak(Box::new(device));
+
+        let mut sounds = HashMap::new();
+        for sfx in Sfx::ALL {
+            let path = format!("{}/{}", SFX_DIR, sfx.file_name());
+            match device.new_sound(&path) {
+                Ok(sound) => { sounds.insert(sfx, sound); }
+                Err(e) => tracing::warn!("{path}: {e}"),
+            }
+        }
+        Some(Self { device, stems: Vec::new(), sounds })
+    }
+}
+
+impl AudioBackend for RaylibAudioBackend {
+    fn play_music(&mut self, stems: &[&str]) {
+        self.stop_music();
+        // load everything first so the stems start on the same frame
+        self.stems = stems.iter().map(|path| match self.device.new_music(path) {
+            Ok(music) => Some(music),
+            Err(e) => {
+                tracing::warn!("{path}: {e}");
+                None
+            }
+        }).collect();
+        for music in self.stems.iter().flatten() {
+            music.set_volume(0.0);
+            music.play_stream();
+        }
+    }
+
+    fn stop_music(&mut self) {
+        for music in self.stems.drain(..).flatten() { music.stop_stream(); }
+    }
+
+    fn set_stem_volume(&mut self, stem: usize, volume: f32) {
+        if let Some(Some(music)) = self.stems.get(stem) { music.set_volume(volume); }
+    }
+
+    fn update(&mut self) {
+        for music in self.stems.iter().flatten() { music.update_stream(); }
+    }
+
+    fn play_sound(&mut self, sfx: Sfx, volume: f32) {
+        if let Some(sound) = self.sounds.get(&sfx) {
+            sound.set_volume(volume);
+            sound.play();
+        }
+    }
+}
+
+pub struct AudioManager {
+    backend: Box<dyn AudioBackend>,
+    volumes: Volumes,
+    // the track that was asked for last, even if it failed to load - so a missing
+    // file isn't retried every frame
+    track: Option<AdaptiveTrack>,
+    mix: LayerMix,
+    // current level of each stem, base first, fading towards the mix
+    levels: Vec<f32>,
+}
+
+impl AudioManager {
+    pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+        Self { backend, volumes: Volumes::default(), track: None, mix: LayerMix::default(), levels: Vec::new() }
+    }
+
+    /// Real audio if there's a device, silence otherwise.
+    pub fn init() -> Self {
+        match RaylibAudioBackend::init() {
+            Some(backend) => Self::new(Box::new(backend)),
+            None => Self::null(),
+        }
+    }
+
+    pub fn null() -> Self {
+        Self::new(Box::new(NullAudio::default()))
+    }
+
+    /// Switches to a plain track unless it's already playing. Safe to call every frame.
+    pub fn play_music(&mut self, path: &'static str) {
+        self.play_adaptive(&AdaptiveTrack::single(path));
+    }
+
+    /// Switches to a layered track unless it's already playing. The base comes in at full
+    /// level straight away, layers start silent and follow the mix. Safe to call every frame.
+    pub fn play_adaptive(&mut self, track: &AdaptiveTrack) {
+        if self.track.as_ref() == Some(track) { return; }
+        self.backend.play_music(&track.paths());
+        self.levels = vec![0.0; track.layers.len() + 1];
+        self.levels[0] = 1.0;
+        self.mix = LayerMix::default();
+        self.track = Some(track.clone());
+        self.apply_levels();
+    }
+
+    pub fn stop_music(&mut self) {
+        self.track = None;
+        self.levels.clear();
+        self.backend.stop_music();
+    }
+
+    pub fn current_track(&self) -> Option<&AdaptiveTrack> {
+        self.track.as_ref()
+    }
+
+    /// Where the layers of the current track should be heading.
+    pub fn set_mix(&mut self, mix: LayerMix) {
+        self.mix = mix;
+    }
+
+    pub fn play(&mut self, sfx: Sfx) {
+        let volume = self.volumes.master * self.volumes.sfx;
+        if volume > 0.0 { self.backend.play_sound(sfx, volume); }
+    }
+
+    pub fn volumes(&self) -> Volumes {
+        self.volumes
+    }
+
+    pub fn set_volume(&mut self, category: VolumeCategory, volume: f32) {
+        self.volumes.set(category, volume);
+        self.apply_levels();
+    }
+
+    /// Fades the layers and feeds the streams. Called once a frame.
+    pub fn update(&mut self, dt: f32) {
+        if let Some(track) = &self.track {
+            let step = STEM_FADE_RATE * dt;
+            for (level, (layer, _)) in self.levels.iter_mut().skip(1).zip(&track.layers) {
+                let target = self.mix.get(*layer).clamp(0.0, 1.0);
+                *level += (target - *level).clamp(-step, step);
+            }
+            self.apply_levels();
+        }
+        self.backend.update();
+    }
+
+    fn apply_levels(&mut self) {
+        let music = self.volumes.master * self.volumes.music;
+        for (i, level) in self.levels.iter().enumerate() {
+            self.backend.set_stem_volume(i, level * music);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // the manager owns its backend, so tests keep a second handle on the null one
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<NullAudio>>);
+
+    impl AudioBackend for Shared {
+        fn play_music(&mut self, stems: &[&str]) { self.0.borrow_mut().play_music(stems) }
+        fn stop_music(&mut self) { self.0.borrow_mut().stop_music() }
+        fn set_stem_volume(&mut self, stem: usize, volume: f32) { self.0.borrow_mut().set_stem_volume(stem, volume) }
+        fn update(&mut self) { self.0.borrow_mut().update() }
+        fn play_sound(&mut self, sfx: Sfx, volume: f32) { self.0.borrow_mut().play_sound(sfx, volume) }
+    }
+
+    fn manager() -> (AudioManager, Rc<RefCell<NullAudio>>) {
+        let backend = Shared::default();
+        let null = backend.0.clone();
+        (AudioManager::new(Box::new(backend)), null)
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn same_track_isnt_restarted() {
+        let (mut audio, null) = manager();
+        audio.play_music(MENU_MUSIC);
+        audio.play_music(MENU_MUSIC);
+        assert_eq!(null.borrow().music_starts, 1);
+        assert_eq!(null.borrow().stems, vec![MENU_MUSIC.to_string()]);
+
+        audio.play_music("./resources/music/other.wav");
+        assert_eq!(null.borrow().music_starts, 2);
+    }
+
+    #[test]
+    fn missing_file_isnt_retried_every_frame() {
+        // the null backend never loads anything, so every track is as good as missing
+        let (mut audio, null) = manager();
+        for _ in 0..10 {
+            audio.play_music("./resources/music/not_there.wav");
+            audio.update(1.0 / 60.0);
+        }
+        assert_eq!(null.borrow().music_starts, 1);
+        assert_eq!(audio.current_track(), Some(&AdaptiveTrack::single("./resources/music/not_there.wav")));
+    }
+
+    #[test]
+    fn volumes_multiply_out() {
+        let (mut audio, null) = manager();
+        audio.set_volume(VolumeCategory::Master, 0.5);
+        audio.set_volume(VolumeCategory::Music, 0.5);
+        audio.set_volume(VolumeCategory::Sfx, 0.4);
+        audio.play_music(MENU_MUSIC);
+        audio.play(Sfx::Hit);
+        assert!(close(null.borrow().stem_volumes[0], 0.25));
+        assert_eq!(null.borrow().played.len(), 1);
+        assert!(close(null.borrow().played[0].1, 0.2));
+
+        // changing a knob reaches what's already playing
+        audio.set_volume(VolumeCategory::Master, 1.0);
+        assert!(close(null.borrow().stem_volumes[0], 0.5));
+
+        // muted effects aren't sent at all
+        audio.set_volume(VolumeCategory::Sfx, 0.0);
+        audio.play(Sfx::Jump);
+        assert_eq!(null.borrow().played.len(), 1);
+    }
+
+    #[test]
+    fn volumes_are_clamped() {
+        let (mut audio, _) = manager();
+        audio.set_volume(VolumeCategory::Music, 3.0);
+        audio.set_volume(VolumeCategory::Sfx, -1.0);
+        assert_eq!(audio.volumes().music, 1.0);
+        assert_eq!(audio.volumes().sfx, 0.0);
+    }
+}
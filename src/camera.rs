@@ -0,0 +1,113 @@
+//! Camera for the duel.
+//!
+//! The lava sets the pace: it drags a scroll line up the tower and the view is never allowed
+//! to sit below it. On top of that the camera follows the wizards, zooming out (up to a limit)
+//! when one races ahead of the other, settles onto the rooftop arena once the climb is over,
+//! and shakes with trauma that big hits and deaths pile on.
+use raylib::prelude::*;
+
+/// World units from the centre of the view to its top or bottom at the base zoom.
+pub const VIEW_HALF_HEIGHT: f32 = 540.0;
+/// Never zoom out past this fraction of the base zoom.
+pub const MIN_ZOOM_SCALE: f32 = 0.65;
+/// Room kept above the highest and below the lowest wizard.
+pub const FRAME_MARGIN: f32 = 260.0;
+
+// how quickly the view catches up, per second
+const FOLLOW_RATE: f32 = 4.0;
+const ZOOM_RATE: f32 = 2.5;
+const ROOFTOP_EASE: f32 = 1.5;
+
+// trauma drains at this rate per second, shake grows with its square
+const TRAUMA_DECAY: f32 = 1.4;
+const MAX_SHAKE_OFFSET: f32 = 40.0;
+const MAX_SHAKE_ANGLE: f32 = 3.0;
+
+pub struct DuelCamera {
+    /// The view without any shake. Draw with [`DuelCamera::view`] instead.
+    pub camera: Camera2D,
+    pub base_zoom: f32,
+    /// Where the lava has dragged the view to. The camera can run ahead of it, never behind.
+    pub scroll_y: f32,
+    /// 0 is still, 1 is as violent as it gets.
+    pub trauma: f32,
+    /// How strongly trauma shows, from the settings. 0 turns shake off.
+    pub shake_scale: f32,
+    shake_time: f32,
+}
+
+impl DuelCamera {
+    pub fn new(target: Vector2, offset: Vector2, zoom: f32) -> Self {
+        Self {
+            camera: Camera2D { target, offset, rotation: 0.0, zoom },
+            base_zoom: zoom,
+            scroll_y: target.y,
+            trauma: 0.0,
+            shake_scale: 1.0,
+            shake_time: 0.0,
+        }
+    }
+
+    pub fn target(&self) -> Vector2 {
+        self.camera.target
+    }
+
+    /// Moves straight to `target`, scroll line and all - for starting mid-tower.
+    pub fn jump_to(&mut self, target: Vector2) {
+        self.camera.target = target;
+        self.scroll_y = target.y;
+    }
+
+    /// Adds to the shake, capped at 1.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    /// Drains trauma. Runs even while the game is frozen in hitstop so the shake still plays out.
+    pub fn update_shake(&mut self, dt: f32) {
+        self.shake_time += dt;
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+    }
+
+    /// Eases towards a view framing every point in `bodies`. Once `on_rooftop` is set the view
+    /// settles onto the arena at `rooftop_y` instead.
+    pub fn follow(&mut self, dt: f32, bodies: &[Vector2], rooftop_y: f32, on_rooftop: bool) {
+        if bodies.is_empty() { return; }
+        let top = bodies.iter().map(|b| b.y).fold(f32::MAX, f32::min);
+        let bottom = bodies.iter().map(|b| b.y).fold(f32::MIN, f32::max);
+
+        // the tower is a fixed-width shaft so only the vertical spread needs fitting
+        let (desired_y, scale, follow_rate, zoom_rate) = if on_rooftop {
+            (rooftop_y, 1.0, ROOFTOP_EASE, ROOFTOP_EASE)
+        } else {
+            let needed = (bottom - top) / 2.0 + FRAME_MARGIN;
+            let scale = (VIEW_HALF_HEIGHT / needed).clamp(MIN_ZOOM_SCALE, 1.0);
+            (((top + bottom) / 2.0).min(self.scroll_y), scale, FOLLOW_RATE, ZOOM_RATE)
+        };
+
+        self.camera.target.y += (desired_y - self.camera.target.y) * (1.0 - (-follow_rate * dt).exp());
+        self.camera.zoom += (self.base_zoom * scale - self.camera.zoom) * (1.0 - (-zoom_rate * dt).exp());
+
+        // easing can't leave the view behind the lava or let it peek over the roof
+        if !on_rooftop {
+            self.camera.target.y = self.camera.target.y.min(self.scroll_y).max(rooftop_y);
+        }
+    }
+
+    /// The camera to draw with, shake included.
+    pub fn view(&self) -> Camera2D {
+        let shake = self.trauma * self.trauma * self.shake_scale;
+        if shake <= 0.0 { return self.camera; }
+
+        // a few out-of-step sine waves make for cheap, smooth noise
+        let t = self.shake_time;
+        let nx = (t * 37.0).sin() * (t * 13.0).cos();
+        let ny = (t * 41.0 + 1.7).sin() * (t * 11.0).cos();
+        let nr = (t * 29.0 + 3.1).sin();
+
+        let mut view = self.camera;
+        view.target += Vector2::new(nx, ny) * (MAX_SHAKE_OFFSET * shake);
+        view.rotation += nr * MAX_SHAKE_ANGLE * shake;
+        view
+    }
+}
@@ -0,0 +1,51 @@
+//! The fixed-size canvas every scene draws onto.
+//!
+//! Scenes lay themselves out for a 1920x1080 screen no matter what the window is. main.rs
+//! renders them into a texture that size and scales it into the window, keeping the aspect
+//! ratio and filling the leftover space with black bars.
+use raylib::prelude::*;
+
+pub const VIRTUAL_WIDTH: i32 = 1920;
+pub const VIRTUAL_HEIGHT: i32 = 1080;
+
+/// Centre of the canvas, handy for camera offsets.
+pub fn center() -> Vector2 {
+    Vector2::new(VIRTUAL_WIDTH as f32 / 2.0, VIRTUAL_HEIGHT as f32 / 2.0)
+}
+
+/// Where the canvas lands inside a window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Letterbox {
+    pub scale: f32,
+    /// Top-left corner of the canvas in window pixels.
+    pub offset: Vector2,
+}
+
+impl Letterbox {
+    /// Biggest fit for a window of the given size, centred.
+    pub fn fit(window_w: i32, window_h: i32) -> Self {
+        let scale = (window_w as f32 / VIRTUAL_WIDTH as f32).min(window_h as f32 / VIRTUAL_HEIGHT as f32);
+        let offset = Vector2::new(
+            (window_w as f32 - VIRTUAL_WIDTH as f32 * scale) / 2.0,
+            (window_h as f32 - VIRTUAL_HEIGHT as f32 * scale) / 2.0,
+        );
+        Self { scale, offset }
+    }
+
+    /// Rectangle to draw the canvas into, in window pixels.
+    pub fn dest(&self) -> Rectangle {
+        Rectangle::new(self.offset.x, self.offset.y, VIRTUAL_WIDTH as f32 * self.scale, VIRTUAL_HEIGHT as f32 * self.scale)
+    }
+
+    /// Converts a window position to canvas coordinates.
+    pub fn to_virtual(&self, point: Vector2) -> Vector2 {
+        (point - self.offset) / self.scale
+    }
+
+    /// Makes raylib report mouse positions in canvas coordinates, so scenes never have to
+    /// think about the window.
+    pub fn apply_to_mouse(&self, rl: &mut RaylibHandle) {
+        rl.set_mouse_offset(Vector2::new(-self.offset.x, -self.offset.y));
+        rl.set_mouse_scale(1.0 / self.scale, 1.0 / self.scale);
+    }
+}
@@ -0,0 +1,197 @@
+//! Command-line flags for launching straight into a set-up match.
+//!
+//! Handy when testing a change over and over: `--skip-menu --stage rooftops --seed 42` lands
+//! in the same climb every time. Flags take their value either as the next argument or after
+//! an `=`. Run with `--help` for the full list.
+use std::fmt;
+use std::path::PathBuf;
+
+use tracing_subscriber::filter::LevelFilter;
+
+use crate::input::Pilot;
+use crate::settings::{DisplayMode, Settings};
+use crate::stage::{self, Stage};
+
+pub const USAGE: &str = "\
+usage: raylib-framework-testing [flags]
+
+  --windowed              start in a window, whatever the settings say (not saved)
+  --resolution WxH        window or fullscreen size, e.g. 1600x900 (not saved)
+  --seed N                tower seed for generated towers
+  --stage NAME            stage to climb with --skip-menu (courtyard, rooftops)
+  --skip-menu             go straight into a match
+  --p1 human|cpu          who plays player 1 (default human)
+  --score-to-win N        points needed to win the match (default 5)
+  --record FILE           go straight into a match and save it to FILE for --replay
+  --replay FILE           play back a match saved with --record
+  --log-level LEVEL       off, error, warn, info, debug or trace (default trace)
+  -h, --help              show this and quit";
+
+/// What the game was launched with. Anything not given leaves the usual behaviour alone.
+#[derive(Debug, Clone)]
+pub struct LaunchOptions {
+    pub windowed: bool,
+    pub resolution: Option<(i32, i32)>,
+    pub seed: Option<u64>,
+    pub stage: Option<Stage>,
+    pub skip_menu: bool,
+    pub p1: Pilot,
+    pub score_to_win: Option<i32>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub log_level: LevelFilter,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self {
+            windowed: false,
+            resolution: None,
+            seed: None,
+            stage: None,
+            skip_menu: false,
+            p1: Pilot::Human,
+            score_to_win: None,
+            record: None,
+            replay: None,
+            log_level: LevelFilter::TRACE,
+        }
+    }
+}
+
+impl LaunchOptions {
+    /// `settings` with the display flags laid over the top, for putting the window up.
+    /// The flags only last for this run, so it's `settings` itself that gets saved.
+    pub fn display_settings(&self, settings: &Settings) -> Settings {
+        let mut shown = settings.clone();
+        if self.windowed { shown.display = DisplayMode::Windowed; }
+        if self.resolution.is_some() { shown.resolution = self.resolution; }
+        shown
+    }
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    /// `--help` was asked for. Not really an error, but it stops the launch all the same.
+    Help,
+    UnknownFlag(String),
+    MissingValue(&'static str),
+    BadValue { flag: &'static str, value: String },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Help => write!(f, "{USAGE}"),
+            CliError::UnknownFlag(flag) => write!(f, "unknown flag {flag}"),
+            CliError::MissingValue(flag) => write!(f, "{flag} needs a value"),
+            CliError::BadValue { flag, value } => write!(f, "{value:?} isn't a valid value for {flag}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+// "1600x900" -> (1600, 900)
+fn parse_resolution(value: &str) -> Option<(i32, i32)> {
+    let (w, h) = value.split_once(['x', 'X'])?;
+    let (w, h) = (w.trim().parse().ok()?, h.trim().parse().ok()?);
+    (w > 0 && h > 0).then_some((w, h))
+}
+
+/// Reads the flags, not counting the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<LaunchOptions, CliError> {
+    let mut options = LaunchOptions::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = |name: &'static str| inline.clone().or_else(|| args.next()).ok_or(CliError::MissingValue(name));
+        let bad = |flag: &'static str, value: String| CliError::BadValue { flag, value };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--windowed" => options.windowed = true,
+            "--skip-menu" => options.skip_menu = true,
+            "--resolution" => {
+                let v = value("--resolution")?;
+                options.resolution = Some(parse_resolution(&v).ok_or_else(|| bad("--resolution", v))?);
+            }
+            "--seed" => {
+                let v = value("--seed")?;
+                options.seed = Some(v.parse().map_err(|_| bad("--seed", v))?);
+            }
+            "--stage" => {
+                let v = value("--stage")?;
+                let found = stage::all().into_iter().find(|s| s.name.eq_ignore_ascii_case(&v));
+                options.stage = Some(found.ok_or_else(|| bad("--stage", v))?);
+            }
+            "--p1" => match value("--p1")?.as_str() {
+                "human" => options.p1 = Pilot::Human,
+                "cpu" => options.p1 = Pilot::Cpu,
+                v => return Err(bad("--p1", v.to_string())),
+            },
+            "--score-to-win" => {
+                let v = value("--score-to-win")?;
+                match v.parse() {
+                    Ok(score) if score > 0 => options.score_to_win = Some(score),
+                    _ => return Err(bad("--score-to-win", v)),
+                }
+            }
+            "--record" => options.record = Some(PathBuf::from(value("--record")?)),
+            "--replay" => options.replay = Some(PathBuf::from(value("--replay")?)),
+            "--log-level" => {
+                let v = value("--log-level")?;
+                options.log_level = v.parse().map_err(|_| bad("--log-level", v))?;
+            }
+            _ => return Err(CliError::UnknownFlag(flag)),
+        }
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn reads_every_flag() {
+        let options = parse(args("--windowed --resolution=1600x900 --seed 42 --stage ROOFTOPS --skip-menu --p1 cpu --score-to-win 3 --record out.json --log-level warn")).unwrap();
+        assert!(options.windowed && options.skip_menu);
+        assert_eq!(options.resolution, Some((1600, 900)));
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.stage.map(|s| s.name), Some(stage::rooftops().name));
+        assert_eq!(options.p1, Pilot::Cpu);
+        assert_eq!(options.score_to_win, Some(3));
+        assert_eq!(options.record, Some(PathBuf::from("out.json")));
+        assert_eq!(options.log_level, LevelFilter::WARN);
+
+        let options = parse(args("--replay=match.json")).unwrap();
+        assert_eq!(options.replay, Some(PathBuf::from("match.json")));
+    }
+
+    #[test]
+    fn bad_flags_are_refused() {
+        assert!(matches!(parse(args("--p1 robot")), Err(CliError::BadValue { flag: "--p1", .. })));
+        assert!(matches!(parse(args("--resolution 1600")), Err(CliError::BadValue { .. })));
+        assert!(matches!(parse(args("--score-to-win 0")), Err(CliError::BadValue { .. })));
+        assert!(matches!(parse(args("--replay")), Err(CliError::MissingValue("--replay"))));
+        assert!(matches!(parse(args("--fly")), Err(CliError::UnknownFlag(_))));
+    }
+
+    #[test]
+    fn display_flags_leave_the_saved_settings_alone() {
+        let options = parse(args("--windowed --resolution 1600x900")).unwrap();
+        let saved = Settings { display: DisplayMode::Fullscreen, resolution: None, ..Settings::default() };
+        let shown = options.display_settings(&saved);
+        assert_eq!((shown.display, shown.resolution), (DisplayMode::Windowed, Some((1600, 900))));
+        assert_eq!((saved.display, saved.resolution), (DisplayMode::Fullscreen, None));
+    }
+}
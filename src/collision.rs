@@ -0,0 +1,195 @@
+//! Player-vs-level collision.
+//!
+//! Resolves a moving hitbox against solid blocks on all four sides and against one-way
+//! platforms from above. Motion is split into small steps so fast falls can't skip
+//! through thin tiles.
+use raylib::prelude::*;
+
+/// Furthest a body moves in one collision step. Kept under the thinnest tile height.
+const MAX_STEP: f32 = 16.0;
+
+/// Slack for deciding a body was above a one-way platform before it moved.
+const ONE_WAY_TOLERANCE: f32 = 1.0;
+
+/// Where a body ended up after moving and what it ran into on the way.
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionResult {
+    pub rect: Rectangle,
+    pub vel: Vector2,
+    pub grounded: bool,
+    /// Index of the one-way platform being stood on, if any.
+    pub ground_platform: Option<usize>,
+    /// -1 if a solid wall stopped us on the left, 1 on the right, 0 otherwise.
+    pub wall: i32,
+    pub hit_ceiling: bool,
+}
+
+/// Strict overlap test, so boxes that are only touching edges don't count.
+pub fn overlaps(a: &Rectangle, b: &Rectangle) -> bool {
+    a.x < b.x + b.width && a.x + a.width > b.x && a.y < b.y + b.height && a.y + a.height > b.y
+}
+
+/// Moves `rect` by `vel * dt`, pushing it out of `solids` on whichever side it hit and
+/// landing it on `one_way` platforms when it falls onto them from above.
+/// `x_bounds` is the span the box has to stay inside (the screen edges), and counts as a wall
+/// while touched. Bounds move with the camera, so a box caught between a bound and a solid
+/// gets popped out on the inner side of the solid rather than pushed back into it.
+/// `skip_one_way` lets the caller ignore specific platforms (e.g. while dropping through).
+pub fn move_and_collide(
+    rect: Rectangle,
+    vel: Vector2,
+    dt: f32,
+    solids: &[Rectangle],
+    one_way: &[Rectangle],
+    x_bounds: (f32, f32),
+    skip_one_way: impl Fn(usize) -> bool,
+) -> CollisionResult {
+    let mut result = CollisionResult {
+        rect,
+        vel,
+        grounded: false,
+        ground_platform: None,
+        wall: 0,
+        hit_ceiling: false,
+    };
+
+    let motion = vel * dt;
+    let steps = (motion.x.abs().max(motion.y.abs()) / MAX_STEP).ceil().max(1.0);
+    let mut step_x = motion.x / steps;
+    let mut step_y = motion.y / steps;
+
+    for _ in 0..steps as i32 {
+        // horizontal pass - the bounds first, then walls
+        result.rect.x += step_x;
+        let (lo, hi) = x_bounds;
+        let mut edge = 0;
+        if result.rect.x <= lo {
+            result.rect.x = lo;
+            edge = -1;
+        } else if result.rect.x + result.rect.width >= hi {
+            result.rect.x = hi - result.rect.width;
+            edge = 1;
+        }
+        if edge != 0 {
+            result.wall = edge;
+            if result.vel.x.signum() == edge as f32 { result.vel.x = 0.0; }
+            if step_x.signum() == edge as f32 { step_x = 0.0; }
+        }
+        if step_x != 0.0 || edge != 0 {
+            for solid in solids {
+                if !overlaps(&result.rect, solid) { continue; }
+                let left_of = solid.x - result.rect.width;
+                let right_of = solid.x + solid.width;
+                result.rect.x = match edge {
+                    // squeezed against the block by an edge - out the far side
+                    -1 => right_of,
+                    1 => left_of,
+                    _ if step_x > 0.0 => { result.wall = 1; left_of }
+                    _ => { result.wall = -1; right_of }
+                };
+                result.vel.x = 0.0;
+                step_x = 0.0;
+            }
+        }
+
+        // vertical pass - floors and ceilings
+        if step_y != 0.0 {
+            let prev_bottom = result.rect.y + result.rect.height;
+            result.rect.y += step_y;
+            for solid in solids {
+                if !overlaps(&result.rect, solid) { continue; }
+                if step_y > 0.0 {
+                    result.rect.y = solid.y - result.rect.height;
+                    result.grounded = true;
+                } else {
+                    result.rect.y = solid.y + solid.height;
+                    result.hit_ceiling = true;
+                }
+                result.vel.y = 0.0;
+                step_y = 0.0;
+            }
+
+            // one-way platforms only catch a body that was above them before this step
+            if step_y > 0.0 {
+                let bottom = result.rect.y + result.rect.height;
+                for (i, plat) in one_way.iter().enumerate() {
+                    if skip_one_way(i) { continue; }
+                    let spans = result.rect.x < plat.x + plat.width && result.rect.x + result.rect.width > plat.x;
+                    if spans && prev_bottom <= plat.y + ONE_WAY_TOLERANCE && bottom >= plat.y {
+                        result.rect.y = plat.y - result.rect.height;
+                        result.vel.y = 0.0;
+                        result.grounded = true;
+                        result.ground_platform = Some(i);
+                        step_y = 0.0;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN: (f32, f32) = (f32::NEG_INFINITY, f32::INFINITY);
+
+    fn body(x: f32, y: f32) -> Rectangle {
+        Rectangle::new(x, y, 50.0, 80.0)
+    }
+
+    #[test]
+    fn fast_fall_doesnt_tunnel_through_a_thin_floor() {
+        let floor = Rectangle::new(-100.0, 500.0, 300.0, 20.0);
+        let moved = move_and_collide(body(0.0, 0.0), Vector2::new(0.0, 40000.0), 1.0 / 60.0, &[floor], &[], OPEN, |_| false);
+        assert!(moved.grounded);
+        assert_eq!(moved.rect.y, 500.0 - 80.0);
+        assert_eq!(moved.vel.y, 0.0);
+    }
+
+    #[test]
+    fn fast_dash_stops_at_a_wall() {
+        let wall = Rectangle::new(200.0, -100.0, 16.0, 300.0);
+        let moved = move_and_collide(body(0.0, 0.0), Vector2::new(30000.0, 0.0), 1.0 / 60.0, &[wall], &[], OPEN, |_| false);
+        assert_eq!(moved.rect.x, 150.0);
+        assert_eq!(moved.wall, 1);
+        assert_eq!(moved.vel.x, 0.0);
+    }
+
+    #[test]
+    fn one_way_platform_catches_from_above_only() {
+        let plat = Rectangle::new(-100.0, 100.0, 300.0, 15.0);
+        let landed = move_and_collide(body(0.0, 0.0), Vector2::new(0.0, 1800.0), 1.0 / 60.0, &[], &[plat], OPEN, |_| false);
+        assert!(landed.grounded);
+        assert_eq!(landed.ground_platform, Some(0));
+        assert_eq!(landed.rect.y, 20.0);
+
+        // jumping up through it from underneath
+        let rising = move_and_collide(body(0.0, 110.0), Vector2::new(0.0, -1800.0), 1.0 / 60.0, &[], &[plat], OPEN, |_| false);
+        assert!(!rising.grounded);
+        assert_eq!(rising.rect.y, 80.0);
+
+        // dropping through on purpose
+        let dropping = move_and_collide(body(0.0, 0.0), Vector2::new(0.0, 1800.0), 1.0 / 60.0, &[], &[plat], OPEN, |_| true);
+        assert!(!dropping.grounded);
+    }
+
+    #[test]
+    fn bounds_act_as_walls() {
+        let moved = move_and_collide(body(0.0, 0.0), Vector2::new(-600.0, 0.0), 1.0 / 60.0, &[], &[], (-5.0, 500.0), |_| false);
+        assert_eq!(moved.rect.x, -5.0);
+        assert_eq!(moved.wall, -1);
+    }
+
+    #[test]
+    fn edge_never_pushes_into_a_block() {
+        // the camera moved and the left edge now sits where the body is, with a block beside it
+        let block = Rectangle::new(20.0, -100.0, 40.0, 300.0);
+        let moved = move_and_collide(body(0.0, 0.0), Vector2::zero(), 1.0 / 60.0, &[block], &[], (10.0, 1000.0), |_| false);
+        assert!(!overlaps(&moved.rect, &block));
+        assert!(moved.rect.x >= 10.0);
+    }
+}
@@ -0,0 +1,172 @@
+//! A computer-controlled wizard.
+//!
+//! Nothing clever: it keeps climbing to the nearest ledge it can reach, keeps a casting
+//! distance from its rival when there's nowhere higher to go, parries or shields bolts that
+//! get close, and throws spells at a mix of charge levels. It only ever produces a
+//! [`PlayerInput`], the same as a person at the keyboard, so it plays by the same rules.
+use raylib::prelude::*;
+
+use crate::input::PlayerInput;
+use crate::platform::{CrumbleState, Platform, PlatformKind};
+use crate::player::{Player, BODY_HEIGHT, SHIELD_MAX};
+use crate::projectile::Projectile;
+
+// a ledge counts as reachable if its top is this far above the wizard's feet
+const CLIMB_MIN: f32 = 40.0;
+const CLIMB_MAX: f32 = 280.0;
+// and no further to the side than this
+const CLIMB_SEARCH_WIDTH: f32 = 600.0;
+// jumps once the ledge is this close sideways
+const JUMP_START_GAP: f32 = 120.0;
+// close enough to the middle of the ledge to stop walking
+const ARRIVE_DISTANCE: f32 = 20.0;
+// so a held-down jump doesn't fire the double jump straight away
+const JUMP_COOLDOWN: f32 = 0.3;
+
+// with nothing to climb, hangs around this far from the rival
+const KEEP_DISTANCE: (f32, f32) = (250.0, 500.0);
+const CAST_RANGE: f32 = 1000.0;
+// seconds to hold each cast, in turn: taps, mid charges and the odd full charge
+const CHARGE_PATTERN: [f32; 5] = [0.1, 0.5, 0.1, 1.0, 1.4];
+
+// bolts heading its way inside this range get dealt with
+const DANGER_RANGE: f32 = 300.0;
+const PARRY_RANGE: f32 = 110.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct CpuPlayer {
+    jump_cooldown: f32,
+    charge_goal: f32,
+    shots: usize,
+}
+
+impl CpuPlayer {
+    /// Decides what `me` does this frame.
+    pub fn think(&mut self, dt: f32, me: &Player, rival: &Player, platforms: &[Platform], bolts: &[Projectile]) -> PlayerInput {
+        let mut input = PlayerInput::default();
+        self.jump_cooldown -= dt;
+
+        // aim from the staff at the rival's chest
+        let staff = Vector2::new(me.pos.x, me.pos.y - 60.0);
+        let chest = Vector2::new(rival.pos.x, rival.pos.y - BODY_HEIGHT / 2.0);
+        let to_rival = chest - staff;
+        if to_rival.length() > 0.0 {
+            let aim = to_rival.normalized();
+            input.aim = Some((aim.x, aim.y));
+        }
+
+        // the closest bolt of the rival's that's still coming this way
+        let middle = Vector2::new(me.pos.x, me.pos.y - BODY_HEIGHT / 2.0);
+        let threat = bolts.iter()
+            .filter(|b| b.active && !b.stuck && b.owner_id != me.input_id)
+            .filter(|b| (middle - b.pos).dot(b.vel) > 0.0)
+            .map(|b| b.pos.distance_to(middle))
+            .min_by(|a, b| a.total_cmp(b));
+        if let Some(distance) = threat && distance < DANGER_RANGE {
+            if distance < PARRY_RANGE && me.parry_cooldown <= 0.0 {
+                input.parry_pressed = true;
+            } else if me.shield_hp > SHIELD_MAX * 0.3 {
+                input.shield_held = true;
+                return input;
+            }
+        }
+
+        match reachable_ledge(me.pos, platforms) {
+            Some(ledge) => {
+                let centre = ledge.x + ledge.width / 2.0;
+                if (centre - me.pos.x).abs() > ARRIVE_DISTANCE { input.direction = (centre - me.pos.x).signum(); }
+                let gap = (ledge.x - me.pos.x).max(me.pos.x - (ledge.x + ledge.width)).max(0.0);
+                let below_ledge = me.pos.y > ledge.y;
+                // jump off the ground when close, and again on the way down if it fell short
+                let first_jump = me.grounded && gap < JUMP_START_GAP;
+                let second_jump = !me.grounded && me.vel.y > 0.0 && below_ledge && me.air_jumps_left > 0;
+                if (first_jump || second_jump) && self.jump_cooldown <= 0.0 {
+                    input.jump_pressed = true;
+                    self.jump_cooldown = JUMP_COOLDOWN;
+                }
+            }
+            None => {
+                let dx = rival.pos.x - me.pos.x;
+                if dx.abs() > KEEP_DISTANCE.1 {
+                    input.direction = dx.signum();
+                } else if dx.abs() < KEEP_DISTANCE.0 {
+                    input.direction = -dx.signum();
+                }
+            }
+        }
+        // hold jump all the way up for the full height
+        input.jump_held = input.jump_pressed || (!me.grounded && me.vel.y < 0.0);
+
+        // hold each cast for its turn in the pattern, then let go
+        if me.charging {
+            input.cast_held = me.charge_time < self.charge_goal;
+        } else if !me.shooting && me.shoot_timer <= 0.0 && to_rival.length() < CAST_RANGE {
+            input.cast_held = true;
+            self.charge_goal = CHARGE_PATTERN[self.shots % CHARGE_PATTERN.len()];
+            self.shots += 1;
+        }
+        input
+    }
+}
+
+// the nearest platform (sideways) that's a single jump above `feet`
+fn reachable_ledge(feet: Vector2, platforms: &[Platform]) -> Option<Rectangle> {
+    platforms.iter()
+        .filter(|p| p.crumble == CrumbleState::Intact && p.kind != PlatformKind::Bouncy)
+        .map(|p| p.rect)
+        .filter(|r| r.y <= feet.y - CLIMB_MIN && r.y >= feet.y - CLIMB_MAX)
+        .map(|r| (r, (r.x - feet.x).max(feet.x - (r.x + r.width)).max(0.0)))
+        .filter(|(_, gap)| *gap <= CLIMB_SEARCH_WIDTH)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(r, _)| r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::MovementAbilities;
+
+    fn wizard(id: i32, x: f32, y: f32) -> Player {
+        let mut player = Player::new(id, x, y, MovementAbilities::FULL);
+        player.grounded = true;
+        player
+    }
+
+    fn ledge(x: f32, y: f32, width: f32) -> Platform {
+        Platform::new(Rectangle::new(x, y, width, 40.0), PlatformKind::Static)
+    }
+
+    #[test]
+    fn aims_at_the_rival_and_casts() {
+        let me = wizard(0, 500.0, 800.0);
+        let rival = wizard(1, 900.0, 800.0);
+        let input = CpuPlayer::default().think(0.016, &me, &rival, &[], &[]);
+        let (x, y) = input.aim.unwrap();
+        assert!(x > 0.9 && y.abs() < 0.2);
+        assert!(input.cast_held);
+    }
+
+    #[test]
+    fn jumps_for_a_ledge_above() {
+        let me = wizard(0, 500.0, 800.0);
+        let rival = wizard(1, 1400.0, 800.0);
+        let platforms = [ledge(540.0, 620.0, 200.0), ledge(100.0, 200.0, 200.0)];
+        let input = CpuPlayer::default().think(0.016, &me, &rival, &platforms, &[]);
+        assert_eq!(input.direction, 1.0);
+        assert!(input.jump_pressed && input.jump_held);
+    }
+
+    #[test]
+    fn parries_a_bolt_about_to_land() {
+        let me = wizard(0, 500.0, 800.0);
+        let rival = wizard(1, 900.0, 800.0);
+        let bolt = Projectile::new(Vector2::new(580.0, 760.0), Vector2::new(-800.0, 0.0), 1, Color::RED);
+        let input = CpuPlayer::default().think(0.016, &me, &rival, &[], &[bolt]);
+        assert!(input.parry_pressed);
+
+        // its own bolts aren't a threat
+        let own = Projectile::new(Vector2::new(580.0, 760.0), Vector2::new(-800.0, 0.0), 0, Color::BLUE);
+        let input = CpuPlayer::default().think(0.016, &me, &rival, &[], &[own]);
+        assert!(!input.parry_pressed);
+    }
+}
@@ -0,0 +1,347 @@
+use raylib::prelude::*;
+
+use crate::audio::MENU_MUSIC;
+use crate::canvas::{self, VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+use crate::game_data::GameData;
+use crate::game_scene::GameScene;
+use crate::level::{LevelFile, PlatformDef, PlatformType, SpawnPoint, PLATFORM_HEIGHT};
+use crate::scenes::{Scene, SceneSwitch};
+use crate::stage::Stage;
+use crate::utils::check_collision_point_rect;
+
+// placement snaps to this so rows line up
+const GRID: f32 = 10.0;
+const MIN_WIDTH: f32 = 60.0;
+// grab this close to a platform's right end to resize instead of move
+const RESIZE_HANDLE: f32 = 14.0;
+const PAN_SPEED: f32 = 900.0;
+const UNDO_LIMIT: usize = 100;
+
+// number keys pick what the next platform will be
+const BRUSHES: [(PlatformType, &str); 6] = [
+    (PlatformType::Static, "static"),
+    (PlatformType::MovingHorizontal { range: 150.0, speed: 1.5 }, "moving (horizontal)"),
+    (PlatformType::MovingVertical { range: 60.0, speed: 1.2 }, "moving (vertical)"),
+    (PlatformType::Crumbling, "crumbling"),
+    (PlatformType::Bouncy, "bouncy"),
+    (PlatformType::Ice, "ice"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    Move { index: usize, grab: Vector2 },
+    Resize { index: usize },
+}
+
+pub struct LevelEditorScene {
+    level: LevelFile,
+    // theme used for drawing and playtests
+    stage: Stage,
+    path: String,
+    camera: Camera2D,
+    selected: Option<usize>,
+    drag: Option<Drag>,
+    brush: usize,
+    brush_texture: Option<usize>,
+    undo_stack: Vec<LevelFile>,
+    redo_stack: Vec<LevelFile>,
+    status: String,
+    status_timer: f32,
+}
+
+impl LevelEditorScene {
+    pub fn new(level: LevelFile, stage: Stage, path: &str) -> Self {
+        Self {
+            level,
+            stage,
+            path: path.to_string(),
+            camera: Camera2D {
+                target: Vector2::new(960.0, 540.0),
+                offset: canvas::center(),
+                rotation: 0.0,
+                zoom: 1.0,
+            },
+            selected: None,
+            drag: None,
+            brush: 0,
+            brush_texture: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            status: String::new(),
+            status_timer: 0.0,
+        }
+    }
+
+    // remember the level as it was before an edit
+    fn snapshot(&mut self) {
+        self.undo_stack.push(self.level.clone());
+        if self.undo_stack.len() > UNDO_LIMIT { self.undo_stack.remove(0); }
+        self.redo_stack.clear();
+    }
+
+    fn undo(&mut self) {
+        if let Some(prev) = self.undo_stack.pop() {
+            self.redo_stack.push(std::mem::replace(&mut self.level, prev));
+            self.selected = None;
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(next) = self.redo_stack.pop() {
+            self.undo_stack.push(std::mem::replace(&mut self.level, next));
+            self.selected = None;
+        }
+    }
+
+    fn save(&mut self) {
+        match self.level.save(&self.path) {
+            Ok(()) => self.show_status(format!("saved to {}", self.path)),
+            Err(e) => {
+                tracing::error!("{e}");
+                self.show_status(format!("save failed: {e}"));
+            }
+        }
+    }
+
+    fn show_status(&mut self, msg: String) {
+        self.status = msg;
+        self.status_timer = 3.0;
+    }
+
+    // topmost platform under the cursor, so the one drawn last wins
+    fn platform_at(&self, point: Vector2) -> Option<usize> {
+        self.level.platforms.iter().rposition(|p| check_collision_point_rect(&point, &platform_rect(p)))
+    }
+
+    fn playtest(&self, data: &mut GameData) -> SceneSwitch {
+        data.player_scores = vec![0, 0];
+        let game = GameScene::from_level(self.level.clone(), self.stage.clone());
+        SceneSwitch::Push(Box::new(game.playtest_from(self.camera.target)))
+    }
+}
+
+fn platform_rect(p: &PlatformDef) -> Rectangle {
+    Rectangle::new(p.x, p.y, p.width, PLATFORM_HEIGHT)
+}
+
+fn snap(v: f32) -> f32 {
+    (v / GRID).round() * GRID
+}
+
+// clickable buttons along the top right, in screen space
+fn playtest_button() -> Rectangle {
+    Rectangle::new(VIRTUAL_WIDTH as f32 - 420.0, 20.0, 260.0, 50.0)
+}
+
+fn save_button() -> Rectangle {
+    Rectangle::new(VIRTUAL_WIDTH as f32 - 140.0, 20.0, 120.0, 50.0)
+}
+
+impl Scene for LevelEditorScene {
+    fn handle_input(&mut self, rl: &mut RaylibHandle, data: &mut GameData) -> SceneSwitch {
+        let dt = rl.get_frame_time();
+        let ctrl = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+        let shift = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+
+        // back to the menu
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) { return SceneSwitch::Pop; }
+
+        // ctrl shortcuts
+        if ctrl {
+            if rl.is_key_pressed(KeyboardKey::KEY_Z) && shift { self.redo(); }
+            else if rl.is_key_pressed(KeyboardKey::KEY_Z) { self.undo(); }
+            if rl.is_key_pressed(KeyboardKey::KEY_Y) { self.redo(); }
+            if rl.is_key_pressed(KeyboardKey::KEY_S) { self.save(); }
+            return SceneSwitch::None;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_F5) { return self.playtest(data); }
+
+        // pan around the tower with the keys or the wheel
+        let mut pan = Vector2::zero();
+        if rl.is_key_down(KeyboardKey::KEY_W) || rl.is_key_down(KeyboardKey::KEY_UP) { pan.y -= 1.0; }
+        if rl.is_key_down(KeyboardKey::KEY_S) || rl.is_key_down(KeyboardKey::KEY_DOWN) { pan.y += 1.0; }
+        if rl.is_key_down(KeyboardKey::KEY_A) || rl.is_key_down(KeyboardKey::KEY_LEFT) { pan.x -= 1.0; }
+        if rl.is_key_down(KeyboardKey::KEY_D) || rl.is_key_down(KeyboardKey::KEY_RIGHT) { pan.x += 1.0; }
+        self.camera.target += pan * PAN_SPEED * dt;
+        self.camera.target.y -= rl.get_mouse_wheel_move() * 160.0;
+
+        // brush type - also retypes the selected platform
+        let number_keys = [
+            KeyboardKey::KEY_ONE, KeyboardKey::KEY_TWO, KeyboardKey::KEY_THREE,
+            KeyboardKey::KEY_FOUR, KeyboardKey::KEY_FIVE, KeyboardKey::KEY_SIX,
+        ];
+        for (i, key) in number_keys.into_iter().enumerate() {
+            if !rl.is_key_pressed(key) { continue; }
+            self.brush = i;
+            if let Some(sel) = self.selected {
+                self.snapshot();
+                self.level.platforms[sel].kind = BRUSHES[i].0;
+            }
+        }
+
+        // cycle textures: automatic, then each tile in turn
+        if rl.is_key_pressed(KeyboardKey::KEY_T) {
+            let count = data.obstacle_tex_vec.len();
+            self.brush_texture = match self.brush_texture {
+                None => Some(0),
+                Some(t) if t + 1 < count => Some(t + 1),
+                Some(_) => None,
+            };
+            if let Some(sel) = self.selected {
+                self.snapshot();
+                self.level.platforms[sel].texture = self.brush_texture;
+            }
+        }
+
+        let mouse_screen = rl.get_mouse_position();
+        let mouse = rl.get_screen_to_world2D(mouse_screen, self.camera);
+
+        // spawn points go wherever the cursor is
+        for (i, key) in [KeyboardKey::KEY_F1, KeyboardKey::KEY_F2].into_iter().enumerate() {
+            if !rl.is_key_pressed(key) { continue; }
+            self.snapshot();
+            let spawn = SpawnPoint { x: snap(mouse.x), y: snap(mouse.y) };
+            while self.level.spawn_points.len() <= i { self.level.spawn_points.push(spawn); }
+            self.level.spawn_points[i] = spawn;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_DELETE) && let Some(sel) = self.selected {
+            self.snapshot();
+            self.level.platforms.remove(sel);
+            self.selected = None;
+        }
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            if check_collision_point_rect(&mouse_screen, &playtest_button()) { return self.playtest(data); }
+            if check_collision_point_rect(&mouse_screen, &save_button()) {
+                self.save();
+                return SceneSwitch::None;
+            }
+
+            self.snapshot();
+            if let Some(i) = self.platform_at(mouse) {
+                // grab an existing platform - by its right end to resize, anywhere else to move
+                let rect = platform_rect(&self.level.platforms[i]);
+                self.drag = if mouse.x >= rect.x + rect.width - RESIZE_HANDLE {
+                    Some(Drag::Resize { index: i })
+                } else {
+                    Some(Drag::Move { index: i, grab: Vector2::new(mouse.x - rect.x, mouse.y - rect.y) })
+                };
+                self.selected = Some(i);
+            } else {
+                // empty space - paint a new platform centred on the cursor
+                let width = 240.0;
+                self.level.platforms.push(PlatformDef {
+                    x: snap(mouse.x - width / 2.0),
+                    y: snap(mouse.y - PLATFORM_HEIGHT / 2.0),
+                    width,
+                    kind: BRUSHES[self.brush].0,
+                    texture: self.brush_texture,
+                });
+                let index = self.level.platforms.len() - 1;
+                self.selected = Some(index);
+                self.drag = Some(Drag::Move { index, grab: Vector2::new(width / 2.0, PLATFORM_HEIGHT / 2.0) });
+            }
+        }
+
+        if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+            match self.drag {
+                Some(Drag::Move { index, grab }) => {
+                    let plat = &mut self.level.platforms[index];
+                    plat.x = snap(mouse.x - grab.x);
+                    plat.y = snap(mouse.y - grab.y);
+                }
+                Some(Drag::Resize { index }) => {
+                    let plat = &mut self.level.platforms[index];
+                    plat.width = snap(mouse.x - plat.x).max(MIN_WIDTH);
+                }
+                None => (),
+            }
+        } else if self.drag.take().is_some() && self.undo_stack.last() == Some(&self.level) {
+            // just a click to select - nothing worth undoing
+            self.undo_stack.pop();
+        }
+
+        // right click deletes whatever is under the cursor
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) && let Some(i) = self.platform_at(mouse) {
+            self.snapshot();
+            self.level.platforms.remove(i);
+            self.selected = None;
+        }
+
+        SceneSwitch::None
+    }
+
+    fn update(&mut self, dt: f32, data: &mut GameData) -> SceneSwitch {
+        data.audio.play_music(MENU_MUSIC);
+        if self.status_timer > 0.0 { self.status_timer -= dt; }
+        SceneSwitch::None
+    }
+
+    fn draw(&self, d: &mut RaylibDrawHandle, data: &mut GameData) {
+        d.clear_background(Color::new(40, 40, 55, 255));
+        let time = d.get_time();
+
+        {
+            let mut d_cam = d.begin_mode2D(self.camera);
+
+            // row guides every 160 units, same spacing as the generated tower
+            let view_h = VIRTUAL_HEIGHT as f32 / self.camera.zoom;
+            let top = self.camera.target.y - view_h;
+            let mut y = (top / 160.0).floor() * 160.0;
+            while y < self.camera.target.y + view_h {
+                d_cam.draw_line_ex(Vector2::new(-1000.0, y), Vector2::new(3000.0, y), 1.0, Color::WHITE.alpha(0.08));
+                y += 160.0;
+            }
+
+            // where the climb ends and the final duel starts
+            let roof = self.level.rooftop_y + self.level.rooftop.floor_offset;
+            d_cam.draw_line_ex(Vector2::new(-1000.0, roof), Vector2::new(3000.0, roof), 4.0, Color::GOLD);
+            d_cam.draw_text("ROOFTOP", 40, roof as i32 - 40, 30, Color::GOLD);
+
+            for solid in &self.level.solids {
+                d_cam.draw_rectangle_rec(solid.build(0.0), Color::GRAY);
+            }
+            for hazard in &self.level.hazards {
+                d_cam.draw_rectangle_rec(hazard.build(0.0).rect, Color::RED.alpha(0.5));
+            }
+
+            for (i, def) in self.level.platforms.iter().enumerate() {
+                def.build(0.0).draw(&mut d_cam, &data.obstacle_tex_vec, &self.stage.tileset, i, time);
+                if self.selected == Some(i) {
+                    let rect = platform_rect(def);
+                    d_cam.draw_rectangle_lines_ex(rect, 3.0, Color::YELLOW);
+                    let handle = Rectangle::new(rect.x + rect.width - RESIZE_HANDLE, rect.y, RESIZE_HANDLE, rect.height);
+                    d_cam.draw_rectangle_rec(handle, Color::YELLOW.alpha(0.6));
+                }
+            }
+
+            for (i, spawn) in self.level.spawn_points.iter().enumerate() {
+                let color = if i == 0 { Color::CYAN } else { Color::ORANGE };
+                d_cam.draw_circle_v(spawn.to_vec(), 16.0, color);
+                d_cam.draw_text(&format!("P{}", i + 1), spawn.x as i32 - 14, spawn.y as i32 - 50, 30, color);
+            }
+        }
+
+        // ui overlay
+        let brush_tex = match self.brush_texture {
+            Some(t) => format!("tile {}", t + 1),
+            None => "auto".to_string(),
+        };
+        d.draw_text(&format!("{} - brush: {} / {}", self.level.name, BRUSHES[self.brush].1, brush_tex), 20, 20, 30, Color::WHITE);
+        d.draw_text("LMB place/move (drag right end to resize)  RMB/DEL delete  1-6 type  T texture", 20, 60, 20, Color::LIGHTGRAY);
+        d.draw_text("WASD/wheel pan  F1/F2 spawn points  CTRL+Z/Y undo/redo  CTRL+S save  F5 playtest  BACKSPACE menu", 20, 85, 20, Color::LIGHTGRAY);
+        d.draw_text(&format!("undo: {}  redo: {}", self.undo_stack.len(), self.redo_stack.len()), 20, 110, 20, Color::GRAY);
+        if self.status_timer > 0.0 {
+            d.draw_text(&self.status, 20, VIRTUAL_HEIGHT - 50, 30, Color::GOLD);
+        }
+
+        for (rect, label) in [(playtest_button(), "PLAYTEST HERE"), (save_button(), "SAVE")] {
+            d.draw_rectangle_rec(rect, Color::BLACK.alpha(0.6));
+            d.draw_rectangle_lines_ex(rect, 2.0, Color::WHITE);
+            let w = d.measure_text(label, 24);
+            d.draw_text(label, (rect.x + rect.width / 2.0) as i32 - w / 2, rect.y as i32 + 13, 24, Color::WHITE);
+        }
+    }
+}
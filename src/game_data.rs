@@ -1,14 +1,23 @@
 //! The data for each game session.
 //!
-//! This stores the global game state, including player scores, screen dimensions,
+//! This stores the global game state, including player scores
 //! and all texture assets used across different scenes.
-use raylib::texture::Texture2D;
+use raylib::prelude::*;
+
+use crate::audio::AudioManager;
+use crate::cli::LaunchOptions;
+use crate::settings::Settings;
+
+/// Points a wizard needs to take the match.
+pub const DEFAULT_SCORE_TO_WIN: i32 = 5;
 
 pub struct GameData {
-    pub zoom_factor: f32,       // keeps things proportional on different screens
     pub player_scores: Vec<i32>, // [p1 score, p2 score]
-    pub screen_width: i32,
-    pub screen_height: i32,
+    pub tower_seed: u64,        // same seed, same generated tower
+    pub score_to_win: i32,
+    pub audio: AudioManager,
+    pub settings: Settings,     // saved preferences, see settings.rs
+    pub launch: LaunchOptions,  // command-line flags, see cli.rs
     
     // p1 sprites
     pub player1_run_tex: Vec<Texture2D>,
@@ -36,9 +45,8 @@ pub struct GameData {
 
 impl GameData {
     pub fn new(
-        width: i32,
-        height: i32,
-        zoom: f32,
+        audio: AudioManager,
+        settings: Settings,
         p1_run: Vec<Texture2D>, p1_idle: Vec<Texture2D>, p1_jump: Vec<Texture2D>, p1_hurt: Vec<Texture2D>, p1_attack: Vec<Texture2D>,
         p2_run: Vec<Texture2D>, p2_idle: Vec<Texture2D>, p2_jump: Vec<Texture2D>, p2_hurt: Vec<Texture2D>, p2_attack: Vec<Texture2D>,
         background_tex: Vec<Texture2D>,
@@ -47,10 +55,12 @@ impl GameData {
         lava_tex: Texture2D,
     ) -> Self {
         Self {
-            zoom_factor: zoom,
             player_scores: vec![0, 0],
-            screen_width: width,
-            screen_height: height,
+            tower_seed: rand::random(),
+            score_to_win: DEFAULT_SCORE_TO_WIN,
+            audio,
+            settings,
+            launch: LaunchOptions::default(),
             player1_run_tex: p1_run,
             player1_idle_tex: p1_idle,
             player1_jump_tex: p1_jump,
@@ -77,4 +87,9 @@ impl GameData {
             self.player_scores[player_id] += 1;
         }
     }
+
+    // puts the window in the saved display mode, unless the command line said otherwise
+    pub fn apply_display(&self, rl: &mut RaylibHandle) {
+        self.launch.display_settings(&self.settings).apply_display(rl);
+    }
 }
\ No newline at end of file
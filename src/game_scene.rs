@@ -1,162 +1,597 @@
 use raylib::prelude::*;
+use std::path::PathBuf;
 
 use crate::scenes::{Scene, SceneSwitch};
 use crate::game_data::GameData;
+use crate::audio::{LayerMix, Sfx, ROOFTOP_MUSIC};
+use crate::camera::DuelCamera;
+use crate::canvas::{self, VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
 use crate::utils::*;
-use crate::player::{AnimationState, Player};
-use crate::projectile::Projectile;
+use crate::collision::{move_and_collide, overlaps};
+use crate::cpu::CpuPlayer;
+use crate::input::{read_human, Pilot, PlayerInput};
+use crate::level::{Hazard, HazardType, LevelFile, RecycleMode, SpawnPoint};
+use crate::pause_scene::PauseScene;
+use crate::stage::{self, Stage};
+use crate::particles::{self, ParticleSystem, BLAST, DEATH, IMPACT, JUMP_DUST, LAVA_BUBBLE, LAVA_EMBER, SPELL_TRAIL, UP};
+use crate::platform::{CrumbleState, Platform, PlatformKind, BOUNCE_VELOCITY, ICE_TRACTION};
+use crate::player::{
+    AnimationState, MovementAbilities, Player, BODY_HEIGHT, BODY_WIDTH, CHARGE_LOOP_FRAMES, CHARGE_MOVE_SCALE, DROP_HOLD_TIME, GRAVITY, KNOCKBACK_DRAG,
+    MOVE_SPEED, PARRY_REACH, SHIELD_MAX, SHIELD_MOVE_SCALE, SHIELD_RADIUS, SPAWN_DESCENT_SPEED, WALL_SLIDE_SPEED,
+};
+use crate::projectile::{resolve_clash, Explosion, Projectile};
+use crate::replay::{Playback, Recorder, Replay, ReplayFrame};
+use crate::rounds::{Round, RoundPhase, RoundRules, Tick};
 use crate::win_scene::WinScene;
 
+// where the camera sits at the bottom of the tower
+const CAMERA_START_Y: f32 = 540.0;
+
+// global freeze on impact so hits land with some weight
+const HITSTOP_BASE: f32 = 0.04;
+const HITSTOP_PER_POWER: f32 = 0.02;
+
+// how much each thing rattles the camera
+const HIT_TRAUMA_BASE: f32 = 0.2;
+const HIT_TRAUMA_PER_POWER: f32 = 0.1;
+const KILL_TRAUMA: f32 = 0.6;
+const BURN_TRAUMA: f32 = 0.25;
+
+// the peak layer of the music fades in over the last part of the climb
+const PEAK_MUSIC_FROM: f32 = 0.6;
+
+// falling at least this fast when touching down throws up dust
+const LANDING_DUST_SPEED: f32 = 500.0;
+
+// the shaft is one screen wide no matter how far the camera zooms out - wizards stay this
+// far in from its sides, which get drawn as walls once the zoom shows past them
+const SHAFT_BUFFER: f32 = 64.0;
+
+// where a respawn is allowed to land - below the top of the screen, clear of the rising
+// lava and, if at all possible, a fair way from the other wizard
+const SPAWN_TOP_MARGIN: f32 = 150.0;
+const SPAWN_LAVA_MARGIN: f32 = 300.0;
+const SPAWN_MIN_DISTANCE: f32 = 500.0;
+
 pub struct GameScene {
     players: Vec<Player>,
     gravity: f32,
     projectiles: Vec<Projectile>,
-    platforms: Vec<Rectangle>,
-    camera: Camera2D,
+    explosions: Vec<Explosion>,
+    particles: ParticleSystem,
+    platforms: Vec<Platform>,
+    solids: Vec<Rectangle>,
+    hazards: Vec<Hazard>,
+    level: LevelFile,
+    stage: Stage,
+    camera: DuelCamera,
     is_on_rooftop: bool,
     rooftop_y: f32, 
     scroll_speed: f32,
     lava_y: f32,
+    hitstop_timer: f32,
+    // started from the level editor at this spot - F5 heads back to it
+    playtest: Option<Vector2>,
+    // this frame's controls as read from people's keys and pads, and the cpu's brains
+    inputs: [PlayerInput; 2],
+    cpus: [CpuPlayer; 2],
+    // --record and --replay, see replay.rs
+    recording: Option<Recorder>,
+    playback: Option<Playback>,
+    rules: RoundRules,
+    round: Round,
     // cleaned up unused walk vars to stop the compiler from complaining
 }
 
 impl GameScene {
-    pub fn new(_n: usize, _width: i32, _height: i32, zoom: f32) -> Self {
-        let mut built_platforms = Vec::new();
-        let mut current_y = 1000.0;
-        let spacing = 160.0; 
-        let rows = 45;
-        
-        // procedural platform generation
-        for i in 0..rows {
-            for j in 0..3 {
-                let seed = (i * 13 + j * 31) as i32;
-                let width = 200.0 + (seed % 150) as f32;
-                let x_pos = (seed * 617) % 1500 + 100; 
-                built_platforms.push(Rectangle::new(x_pos as f32, current_y, width, 40.0));
-            }
-            current_y -= spacing;
-        }
+    // builds the tower, hazards and starting spots from a level description,
+    // dressed up and paced by the stage
+    pub fn from_level(level: LevelFile, stage: Stage) -> Self {
+        let spawn = |i: usize, fallback: Vector2| level.spawn_points.get(i).map_or(fallback, |s| s.to_vec());
+        let p1 = spawn(0, Vector2::new(600.0, 800.0));
+        let p2 = spawn(1, Vector2::new(1320.0, 800.0));
 
         Self { 
-            gravity: 2400.0,
+            gravity: GRAVITY,
             players: vec![
-                Player::new(0, 600.0, 800.0), 
-                Player::new(1, 1320.0, 800.0) 
+                Player::new(0, p1.x, p1.y, MovementAbilities::for_character(0)),
+                Player::new(1, p2.x, p2.y, MovementAbilities::for_character(1)),
             ],
             projectiles: Vec::new(),
-            platforms: built_platforms,
-            camera: Camera2D {
-                target: Vector2::new(960.0, 540.0), 
-                offset: Vector2::new(_width as f32 / 2.0, _height as f32 / 2.0), 
-                rotation: 0.0,
-                zoom: zoom,  
-            },
-            scroll_speed: 60.0, 
+            explosions: Vec::new(),
+            particles: ParticleSystem::new(),
+            platforms: level.platforms.iter().map(|p| p.build(0.0)).collect(),
+            solids: level.solids.iter().map(|b| b.build(0.0)).collect(),
+            hazards: level.hazards.iter().map(|h| h.build(0.0)).collect(),
+            camera: DuelCamera::new(Vector2::new(960.0, CAMERA_START_Y), canvas::center(), 1.0),
+            scroll_speed: stage.scroll.start, 
             lava_y: 1100.0,
+            hitstop_timer: 0.0,
+            playtest: None,
+            inputs: Default::default(),
+            cpus: Default::default(),
+            recording: None,
+            playback: None,
+            rules: RoundRules::duel(),
+            round: Round::start(1, &RoundRules::duel()),
             is_on_rooftop: false,
-            rooftop_y: -8500.0,
+            rooftop_y: level.rooftop_y,
+            level,
+            stage,
         }
     }
-}
 
-impl Scene for GameScene {
-    fn on_enter(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) {}
+    // furthest left and right a wizard's `pos` can go
+    fn shaft_edges(&self) -> (f32, f32) {
+        let half_width = VIRTUAL_WIDTH as f32 / 2.0;
+        (self.camera.target().x - half_width + SHAFT_BUFFER, self.camera.target().x + half_width - SHAFT_BUFFER)
+    }
 
-    fn handle_input(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) -> SceneSwitch {
-        for player in &mut self.players {
-            if player.shoot_timer > 0.0 { player.shoot_timer -= _rl.get_frame_time(); }
-            let mut direction = 0.0;
+    // 0 at the bottom of the tower, 1 once the camera reaches the rooftop
+    fn climb_progress(&self) -> f32 {
+        ((CAMERA_START_Y - self.camera.target().y) / (CAMERA_START_Y - self.rooftop_y)).clamp(0.0, 1.0)
+    }
 
-            // controller support
-            if _rl.is_gamepad_available(player.input_id) {
-                let axis_x = _rl.get_gamepad_axis_movement(player.input_id, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
-                if axis_x.abs() > 0.1 { direction = axis_x; player.facing_left = axis_x < 0.0; }
-                
-                if player.grounded && _rl.is_gamepad_button_pressed(player.input_id, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN) {
-                    player.vel.y = -1200.0; 
-                    player.grounded = false;
-                }
-                let aim_input = Vector2::new(
-                    _rl.get_gamepad_axis_movement(player.input_id, GamepadAxis::GAMEPAD_AXIS_RIGHT_X),
-                    _rl.get_gamepad_axis_movement(player.input_id, GamepadAxis::GAMEPAD_AXIS_RIGHT_Y)
-                );
-                if aim_input.length() > 0.1 { player.aim = aim_input.normalized(); }
-            }
-
-            // p1 keyboard controls
-            if player.input_id == 0 {
-                if _rl.is_key_down(KeyboardKey::KEY_A) { direction = -1.0; player.facing_left = true; }
-                if _rl.is_key_down(KeyboardKey::KEY_D) { direction = 1.0; player.facing_left = false; }
-                if player.grounded && (_rl.is_key_pressed(KeyboardKey::KEY_W) || _rl.is_key_pressed(KeyboardKey::KEY_SPACE)) {
-                    player.vel.y = -1200.0;
-                    player.grounded = false;
-                }
-                // use mouse to aim if no gamepad is plugged in
-                if !_rl.is_gamepad_available(player.input_id) {
-                    let world_mouse = _rl.get_screen_to_world2D(_rl.get_mouse_position(), self.camera);
-                    let diff = world_mouse - Vector2::new(player.pos.x, player.pos.y - 60.0);
-                    if diff.length() > 0.0 { player.aim = diff.normalized(); }
-                }
+    // editor playtest - skips the climb up to `target` and drops both wizards there
+    pub fn playtest_from(mut self, target: Vector2) -> Self {
+        self.camera.jump_to(target);
+        self.lava_y = target.y + 480.0;
+        self.players[0].pos = Vector2::new(target.x - 150.0, target.y);
+        self.players[1].pos = Vector2::new(target.x + 150.0, target.y);
+        self.playtest = Some(target);
+        // trying out a section is one long climb, no rounds
+        self.with_rules(RoundRules::practice())
+    }
+
+    pub fn with_rules(mut self, rules: RoundRules) -> Self {
+        self.round = Round::start(self.round.number, &rules);
+        self.rules = rules;
+        self
+    }
+
+    // a recorded match, played back from the start
+    pub fn replaying(replay: Replay) -> Self {
+        // load already turned away replays from unknown stages
+        let stage = replay.stage().unwrap_or_else(|_| stage::courtyard());
+        let mut game = GameScene::from_level(replay.level.clone(), stage);
+        game.playback = Some(Playback::new(replay));
+        game
+    }
+
+    // saves the match to `path` at the end of every round
+    pub fn recording_to(mut self, path: PathBuf, score_to_win: i32) -> Self {
+        self.recording = Some(Recorder { path, replay: Replay::new(&self.stage, self.level.clone(), score_to_win) });
+        self
+    }
+
+    // the same match from the very start, for the pause menu's restart
+    fn restarted(&self) -> GameScene {
+        let game = GameScene::from_level(self.level.clone(), self.stage.clone());
+        let game = match self.playtest {
+            Some(target) => game.playtest_from(target),
+            None => game,
+        };
+        // a recording or replay starts over with it
+        GameScene {
+            recording: self.recording.as_ref().map(Recorder::restarted),
+            playback: self.playback.as_ref().map(Playback::restarted),
+            ..game.with_rules(self.rules)
+        }
+    }
+
+    // a kill hands the point over and, in a duel, ends the round there and then.
+    // without rounds the loser just drops back in from above
+    fn score_kill(&mut self, victim: usize, scorer: usize, data: &mut GameData) {
+        if self.rules.ends_on_kill {
+            if self.round.finish(scorer) {
+                data.player_scores[scorer] += 1;
+                data.audio.play(Sfx::Stinger);
             }
+            return;
+        }
+        data.player_scores[scorer] += 1;
+        self.drop_in(victim);
+    }
+
+    // back in with full hearts from the top of the screen, floating down onto the safest platform
+    fn drop_in(&mut self, i: usize) {
+        let (landing, platform) = self.safe_respawn(i);
+        let top = self.camera.target().y - VIRTUAL_HEIGHT as f32 / 2.0;
+        let start = if platform.is_some() { Vector2::new(landing.x, top) } else { landing };
+        self.players[i].respawn(start, platform);
+    }
+
+    // the highest platform on screen that isn't about to drop, bounce or burn the wizard,
+    // at whichever end is furthest from the opponent. anything closer than
+    // SPAWN_MIN_DISTANCE only gets picked if there's nowhere else
+    fn safe_respawn(&self, victim: usize) -> (Vector2, Option<usize>) {
+        let opponent = self.players[rival(&self.players[victim])].pos;
+        let target = self.camera.target();
+        let left = target.x - VIRTUAL_WIDTH as f32 / 2.0 + BODY_WIDTH * 2.0;
+        let right = target.x + VIRTUAL_WIDTH as f32 / 2.0 - BODY_WIDTH * 2.0;
+        let top = target.y - VIRTUAL_HEIGHT as f32 / 2.0 + SPAWN_TOP_MARGIN;
+        let bottom = self.lava_y - SPAWN_LAVA_MARGIN;
+
+        let spots = self.platforms.iter().enumerate().filter_map(|(i, plat)| {
+            let standable = matches!(plat.kind, PlatformKind::Static | PlatformKind::Ice | PlatformKind::Moving { .. });
+            let r = plat.rect;
+            if !standable || plat.crumble != CrumbleState::Intact || r.y < top || r.y > bottom { return None; }
+            let (lo, hi) = ((r.x + BODY_WIDTH).max(left), (r.x + r.width - BODY_WIDTH).min(right));
+            if lo > hi { return None; }
+            let x = if (opponent.x - lo).abs() > (opponent.x - hi).abs() { lo } else { hi };
+            let spot = Vector2::new(x, r.y);
+            let body = Rectangle::new(x - BODY_WIDTH / 2.0, r.y - BODY_HEIGHT, BODY_WIDTH, BODY_HEIGHT);
+            let blocked = self.hazards.iter().any(|h| overlaps(&body, &h.rect)) || self.solids.iter().any(|s| overlaps(&body, s));
+            (!blocked).then_some((spot, i))
+        }).collect::<Vec<_>>();
 
-            // p2 keyboard controls
-            if player.input_id == 1 {
-                 if _rl.is_key_down(KeyboardKey::KEY_LEFT) { direction = -1.0; player.facing_left = true; }
-                 if _rl.is_key_down(KeyboardKey::KEY_RIGHT) { direction = 1.0; player.facing_left = false; }
-                 if player.grounded && (_rl.is_key_pressed(KeyboardKey::KEY_UP) || _rl.is_key_pressed(KeyboardKey::KEY_RIGHT_CONTROL)) {
-                      player.vel.y = -1200.0;
-                      player.grounded = false;
-                 }
+        let highest = spots.iter()
+            .filter(|(spot, _)| spot.distance_to(opponent) >= SPAWN_MIN_DISTANCE)
+            .min_by(|a, b| a.0.y.total_cmp(&b.0.y));
+        let furthest = || spots.iter().max_by(|a, b| a.0.distance_to(opponent).total_cmp(&b.0.distance_to(opponent)));
+        match highest.or_else(furthest) {
+            Some(&(spot, i)) => (spot, Some(i)),
+            // nothing to stand on at all - drop in from above like before
+            None => (Vector2::new(target.x, self.camera.scroll_y - 400.0), None),
+        }
+    }
+
+    // moves the wizards as their controls say, whoever's at them
+    fn apply_inputs(&mut self, inputs: &[PlayerInput; 2], dt: f32, data: &mut GameData) {
+        // hands off during the countdown and once the round's been decided
+        if !self.round.is_fighting() {
+            for player in &mut self.players {
+                player.move_input = 0.0;
+                player.shielding = false;
+                if player.charging { player.cancel_charge(); }
+                if !player.in_hitstun() { player.vel.x = 0.0; }
             }
+            return;
+        }
 
-            player.vel.x = direction * 350.0;
+        for (player, input) in self.players.iter_mut().zip(inputs) {
+            if player.shoot_timer > 0.0 { player.shoot_timer -= dt; }
 
-            // shooting logic for both players
-            let mut shoot_pressed = false;
-            if player.input_id == 0 && _rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) { shoot_pressed = true; }
-            if player.input_id == 1 && _rl.is_key_pressed(KeyboardKey::KEY_ENTER) { shoot_pressed = true; }
-            if _rl.is_gamepad_available(player.input_id) && _rl.get_gamepad_axis_movement(player.input_id, GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER) > 0.5 { shoot_pressed = true; }
+            // reeling from a hit - keep the knockback velocity instead of reading movement
+            if player.in_hitstun() {
+                player.shielding = false;
+                player.move_input = 0.0;
+                continue;
+            }
 
-            if shoot_pressed && player.shoot_timer <= 0.0 {
+            // a broken shield leaves the wizard dazed and ignoring input
+            if player.is_stunned() {
+                player.vel.x = 0.0;
+                player.shielding = false;
+                player.move_input = 0.0;
+                continue;
+            }
+            // mid-dash the burst owns the wizard's velocity, and a respawn floats down on its own
+            if player.is_dashing() || player.is_spawning() { continue; }
+
+            let direction = input.direction;
+            if direction != 0.0 { player.facing_left = direction < 0.0; }
+            if let Some(aim) = input.aim() { player.aim = aim; }
+            player.move_input = direction;
+            let mut jump_pressed = input.jump_pressed;
+
+            // down+jump, or just holding down, drops through the platform underfoot.
+            // the rooftop floor is the only thing between the duel and the lava so it stays solid
+            if input.drop_held && player.grounded { player.down_hold_time += dt; } else { player.down_hold_time = 0.0; }
+            if let Some(plat) = player.ground_platform
+                && player.grounded && !self.is_on_rooftop
+                && input.drop_held && (jump_pressed || player.down_hold_time >= DROP_HOLD_TIME) {
+                player.start_drop(plat);
+                jump_pressed = false;
+            }
+
+            if jump_pressed { player.buffer_jump(); }
+            if player.try_buffered_jump() {
+                self.particles.burst(&JUMP_DUST, player.pos, UP);
+                data.audio.play(Sfx::Jump);
+            }
+            if !input.jump_held && player.jumping { player.release_jump(); }
+            if input.dash_pressed {
+                player.try_dash();
+                if player.is_dashing() { continue; }
+            }
+
+            player.shielding = input.shield_held;
+            if player.is_shielding() && player.charging { player.cancel_charge(); }
+
+            // wizards shuffle slowly while winding up a spell or hiding behind a shield
+            let move_scale = if player.is_shielding() {
+                SHIELD_MOVE_SCALE
+            } else if player.charging {
+                CHARGE_MOVE_SCALE
+            } else {
+                1.0
+            };
+            // right after a wall jump the push-off wins over the stick,
+            // and on ice the wizard only slowly picks up or loses speed
+            let target_vel = direction * MOVE_SPEED * move_scale;
+            if player.wall_jump_lock <= 0.0 {
+                if player.on_ice {
+                    player.vel.x += (target_vel - player.vel.x) * (ICE_TRACTION * dt).min(1.0);
+                } else {
+                    player.vel.x = target_vel;
+                }
+            }
+
+            // shooting logic for both players - hold to charge, let go to cast
+            let fire_down = input.cast_held;
+            if fire_down && !player.shooting && !player.is_shielding() && player.shoot_timer <= 0.0 {
+                // going on the attack gives up the spawn protection
+                player.spawn_protection = 0.0;
+                player.shooting = true;
+                player.charging = true;
+                player.charge_time = 0.0;
+            }
+            if player.charging && !fire_down {
+                // released - update lets the attack anim finish and fires the bolt
+                player.charging = false;
                 player.shoot_timer = 0.8;
-                player.shooting = true; 
             }
+
+            if input.parry_pressed { player.try_parry(); }
+        }
+    }
+
+    // a respawn's platform can be recycled up the tower, crumble away or sink under the
+    // lava before the wizard gets there - aim for another one below, or stop floating and fall
+    fn retarget_respawns(&mut self) {
+        for i in 0..self.players.len() {
+            let Some(target) = self.players[i].spawn_platform else { continue };
+            let feet = self.players[i].pos;
+            let usable = |plat: &Platform| plat.is_solid() && plat.rect.y >= feet.y && plat.rect.y < self.lava_y;
+            if self.platforms.get(target).is_some_and(usable) { continue; }
+
+            match self.safe_respawn(i) {
+                (spot, Some(new)) if self.platforms[new].rect.y >= feet.y => {
+                    self.players[i].pos.x = spot.x;
+                    self.players[i].spawn_platform = Some(new);
+                }
+                _ => self.players[i].finish_spawn(None),
+            }
+        }
+    }
+
+    // intermission's over - either someone's won or the tower gets rebuilt for another go
+    fn next_round(&mut self, data: &mut GameData) -> SceneSwitch {
+        if let Some(recording) = &self.recording { recording.save(); }
+        if data.player_scores.iter().any(|s| *s >= data.score_to_win) {
+            data.audio.play(Sfx::Victory);
+            return SceneSwitch::Push(Box::new(WinScene));
+        }
+        let round = Round::start(self.round.number + 1, &self.rules);
+        // the recording or replay carries on into the next round
+        let (recording, playback) = (self.recording.take(), self.playback.take());
+        *self = GameScene { round, recording, playback, ..self.restarted() };
+        // both wizards come in like a respawn, away from each other and protected till they land
+        for i in 0..self.players.len() {
+            self.drop_in(i);
+        }
+        SceneSwitch::None
+    }
+
+    // what each wizard's pilot wants to do this frame
+    fn pilot_inputs(&mut self, dt: f32, data: &GameData) -> [PlayerInput; 2] {
+        let pilots = [data.launch.p1, Pilot::Human];
+        let mut inputs = self.inputs;
+        for (i, pilot) in pilots.into_iter().enumerate() {
+            if pilot == Pilot::Cpu {
+                let (me, rival) = (&self.players[i], &self.players[rival(&self.players[i])]);
+                inputs[i] = self.cpus[i].think(dt, me, rival, &self.platforms, &self.projectiles);
+            }
+        }
+        inputs
+    }
+}
+
+// sprite list for a player's current animation - the shield pose reuses the staff raise
+// from the attack frames, being dazed loops the hurt frames, dashes use the run cycle
+// and wall slides the jump frames
+fn anim_textures(data: &GameData, player_index: usize, state: AnimationState) -> &Vec<Texture2D> {
+    match (player_index, state) {
+        (0, AnimationState::Idle) => &data.player1_idle_tex,
+        (0, AnimationState::Run | AnimationState::Dash) => &data.player1_run_tex,
+        (0, AnimationState::Jump | AnimationState::WallSlide) => &data.player1_jump_tex,
+        (0, AnimationState::Shoot | AnimationState::Shield) => &data.player1_attack_tex,
+        (0, AnimationState::Hurt | AnimationState::Stunned) => &data.player1_hurt_tex,
+        (_, AnimationState::Idle) => &data.player2_idle_tex,
+        (_, AnimationState::Run | AnimationState::Dash) => &data.player2_run_tex,
+        (_, AnimationState::Jump | AnimationState::WallSlide) => &data.player2_jump_tex,
+        (_, AnimationState::Shoot | AnimationState::Shield) => &data.player2_attack_tex,
+        (_, AnimationState::Hurt | AnimationState::Stunned) => &data.player2_hurt_tex,
+    }
+}
+
+// everything a landed spell does to whoever it hit
+struct Hit {
+    damage: i32,
+    power: u32,
+    direction: Vector2,
+    attacker_id: i32,
+}
+
+impl Hit {
+    fn from_bolt(p: &Projectile, direction: Vector2) -> Self {
+        Self { damage: p.damage, power: p.power(), direction, attacker_id: p.owner_id }
+    }
+
+    fn hitstop(&self) -> f32 {
+        HITSTOP_BASE + HITSTOP_PER_POWER * self.power as f32
+    }
+
+    fn trauma(&self) -> f32 {
+        HIT_TRAUMA_BASE + HIT_TRAUMA_PER_POWER * self.power as f32
+    }
+
+    // which way the sparks fly off the victim
+    fn angle(&self) -> f32 {
+        self.direction.y.atan2(self.direction.x)
+    }
+}
+
+// knocks off hp and sends the victim flying - the finishing blow too, so it plays out
+// in the slow motion. returns true if it was a kill
+fn damage_player(player: &mut Player, hit: &Hit) -> bool {
+    player.hp -= hit.damage;
+    player.i_frame_timer = 1.5;
+    player.hit = true;
+    player.cancel_charge();
+    player.apply_knockback(hit.direction, hit.power);
+    player.is_down()
+}
+
+// lava and hazards take a heart and pop the wizard back up. returns true if it actually burned
+fn burn_player(player: &mut Player) -> bool {
+    if player.is_invincible() { return false; }
+    player.hp -= 1; player.i_frame_timer = 1.0; player.vel.y = -650.0; player.hit = true;
+    true
+}
+
+// nobody cast the killing blow, so the point goes to the other wizard
+fn rival(player: &Player) -> usize {
+    if player.input_id == 0 { 1 } else { 0 }
+}
+
+impl Scene for GameScene {
+    fn on_enter(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) {}
+
+    fn handle_input(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) -> SceneSwitch {
+        if self.playtest.is_some() && _rl.is_key_pressed(KeyboardKey::KEY_F5) { return SceneSwitch::Pop; }
+
+        // escape or either start button freezes the match under the pause menu
+        if _rl.is_key_pressed(KeyboardKey::KEY_ESCAPE)
+            || _rl.is_gamepad_button_pressed(0, GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT)
+            || _rl.is_gamepad_button_pressed(1, GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT) {
+            return SceneSwitch::Push(Box::new(PauseScene::new(self.restarted())));
+        }
+
+        // people's controls are read here and applied in update, along with the cpu's
+        let view = self.camera.view();
+        for (i, player) in self.players.iter().enumerate() {
+            // p1 aims with the mouse if no gamepad is plugged in
+            let mouse_aim = (player.input_id == 0)
+                .then(|| _rl.get_screen_to_world2D(_rl.get_mouse_position(), view) - Vector2::new(player.pos.x, player.pos.y - 60.0));
+            self.inputs[i] = read_human(_rl, &_data.settings.controls[player.input_id as usize], player.input_id, mouse_aim);
         }
         SceneSwitch::None
     }
 
     fn update(&mut self, dt: f32, _data: &mut GameData) -> SceneSwitch {
+        // this frame's controls, and its length when it's a replay's
+        let (dt, inputs) = match &mut self.playback {
+            Some(playback) => match playback.next_frame() {
+                Some(frame) => (frame.dt, frame.inputs),
+                // that's the whole recording
+                None => return SceneSwitch::Pop,
+            },
+            None => (dt, self.pilot_inputs(dt, _data)),
+        };
+        if let Some(recording) = &mut self.recording { recording.record(ReplayFrame { dt, inputs }); }
+        self.apply_inputs(&inputs, dt, _data);
+
+        if self.is_on_rooftop {
+            _data.audio.play_music(ROOFTOP_MUSIC);
+        } else {
+            // the stage's music builds up with the pace, the height and how close the duel is to ending
+            _data.audio.play_adaptive(&self.stage.music);
+            let pace = self.stage.scroll;
+            _data.audio.set_mix(LayerMix {
+                drive: ((self.scroll_speed - pace.start) / (pace.max - pace.start)).clamp(0.0, 1.0),
+                peak: ((self.climb_progress() - PEAK_MUSIC_FROM) / (1.0 - PEAK_MUSIC_FROM)).clamp(0.0, 1.0),
+                tension: if self.players.iter().any(|p| p.hp == 1) { 1.0 } else { 0.0 },
+            });
+        }
+        self.camera.shake_scale = _data.settings.screen_shake;
+        self.camera.update_shake(dt);
+
+        // the round decides how much of the frame actually happens
+        let dt = match self.round.tick(dt, &self.rules) {
+            Tick::Frozen => return SceneSwitch::None,
+            Tick::Run(dt) => dt,
+            Tick::NextRound => return self.next_round(_data),
+        };
+
+        // everything holds still for a few frames after a big hit
+        if self.hitstop_timer > 0.0 {
+            self.hitstop_timer -= dt;
+            return SceneSwitch::None;
+        }
+
         if !self.is_on_rooftop {
-            // scrolling up gets faster over time
-            let growth_factor = 0.09; 
-            self.scroll_speed += (self.scroll_speed * growth_factor) * dt;
-            if self.scroll_speed > 250.0 { self.scroll_speed = 250.0; }
-            self.camera.target.y -= self.scroll_speed * dt;
-            self.lava_y = self.camera.target.y + 480.0;
-
-            // check if we reached the roof for the final duel
-            if self.camera.target.y <= self.rooftop_y {
+            // scrolling up gets faster over time, at the stage's pace
+            let curve = self.stage.scroll;
+            self.scroll_speed += (self.scroll_speed * curve.growth) * dt;
+            if self.scroll_speed > curve.max { self.scroll_speed = curve.max; }
+            self.camera.scroll_y -= self.scroll_speed * dt;
+            self.lava_y = self.camera.scroll_y + 480.0;
+
+            // check if we reached the roof for the final duel - the camera eases the rest of the way
+            if self.camera.scroll_y <= self.rooftop_y {
                 self.is_on_rooftop = true;
-                self.camera.target.y = self.rooftop_y;
+                _data.audio.play(Sfx::Stinger);
+                self.camera.scroll_y = self.rooftop_y;
+                // the floor always goes first so draw can pick it out
+                let arena = &self.level.rooftop;
                 self.platforms.clear(); 
-                self.platforms.push(Rectangle::new(-5000.0, self.rooftop_y + 400.0, 10000.0, 100.0));
+                self.platforms.push(Platform::new(Rectangle::new(-5000.0, self.rooftop_y + arena.floor_offset, 10000.0, 100.0), PlatformKind::Static));
+                self.platforms.extend(arena.platforms.iter().map(|p| p.build(self.rooftop_y)));
+                self.solids = arena.solids.iter().map(|b| b.build(self.rooftop_y)).collect();
+                self.hazards = arena.hazards.iter().map(|h| h.build(self.rooftop_y)).collect();
+
+                // both wizards start the final duel on the arena's spawn points,
+                // or on the floor at the usual spots if it doesn't have any. a respawn
+                // still floating down was aiming at a platform that's gone now
+                for (i, player) in self.players.iter_mut().enumerate() {
+                    let fallback = SpawnPoint { x: if i == 0 { 600.0 } else { 1320.0 }, y: arena.floor_offset };
+                    let spawn = arena.spawn_points.get(i).copied().unwrap_or(fallback);
+                    if player.is_spawning() { player.finish_spawn(None); }
+                    player.teleport(Vector2::new(spawn.x, self.rooftop_y + spawn.y));
+                }
             }
 
-            // platform recycling to keep the tower going
-            let tower_height = 7200.0;
+            // platform recycling to keep the tower going - repeating towers reuse their
+            // section as-is, reshuffled ones scatter it, generated ones are tall enough to never need it
+            let tower_height = self.level.tower_height;
             let bottom_limit = self.lava_y + 100.0;
-            for plat in &mut self.platforms {
-                if plat.y > bottom_limit {
-                    plat.y -= tower_height;
-                    let seed = (plat.y.abs() as i32 + plat.x as i32) * 17;
-                    plat.x = 100.0 + (seed % 1400) as f32;
-                    plat.width = 200.0 + (seed % 150) as f32;
+            if !self.is_on_rooftop && self.level.recycle != RecycleMode::None {
+                for plat in &mut self.platforms {
+                    if plat.rect.y > bottom_limit {
+                        let y = plat.rect.y - tower_height;
+                        if self.level.recycle == RecycleMode::Reshuffle {
+                            let seed = (y.abs() as i32 + plat.rect.x as i32) * 17;
+                            plat.relocate(100.0 + (seed % 1400) as f32, y, 200.0 + (seed % 150) as f32);
+                        } else {
+                            plat.shift_y(-tower_height);
+                        }
+                    }
+                }
+                for solid in &mut self.solids {
+                    if solid.y > bottom_limit { solid.y -= tower_height; }
                 }
+                for hazard in &mut self.hazards {
+                    if hazard.rect.y > bottom_limit { hazard.rect.y -= tower_height; }
+                }
+            }
+        }
+
+        // platforms move first so anyone riding one gets carried along
+        for plat in &mut self.platforms { plat.update(dt); }
+        for player in &mut self.players {
+            if let Some(i) = player.ground_platform.filter(|_| player.grounded) {
+                player.pos += self.platforms[i].delta;
             }
         }
 
         for player in &mut self.players {
+            if player.charging { player.charge_time += dt; }
+            if player.parry_timer > 0.0 { player.parry_timer -= dt; }
+            if player.parry_cooldown > 0.0 { player.parry_cooldown -= dt; }
+            player.update_shield(dt);
+
             // figure out what animation should be playing
             let new_state = player.determine_anim_state();
             if new_state != player.anim_state {
@@ -167,18 +602,7 @@ impl Scene for GameScene {
             }
 
             // pick the right texture list
-            let tex_vec = match (player.input_id, player.anim_state) {
-                (0, AnimationState::Idle) => &_data.player1_idle_tex,
-                (0, AnimationState::Run) => &_data.player1_run_tex,
-                (0, AnimationState::Jump) => &_data.player1_jump_tex,
-                (0, AnimationState::Shoot) => &_data.player1_attack_tex,
-                (0, AnimationState::Hurt) => &_data.player1_hurt_tex,
-                (1, AnimationState::Idle) => &_data.player2_idle_tex,
-                (1, AnimationState::Run) => &_data.player2_run_tex,
-                (1, AnimationState::Jump) => &_data.player2_jump_tex,
-                (1, AnimationState::Shoot) => &_data.player2_attack_tex,
-                _ => &_data.player2_hurt_tex,
-            };
+            let tex_vec = anim_textures(_data, player.input_id as usize, player.anim_state);
 
             // handle frame timing
             player.anim_timer += dt;
@@ -186,82 +610,261 @@ impl Scene for GameScene {
                 player.anim_timer = 0.0;
                 let max_frames = tex_vec.len();
                 match player.anim_state {
-                    AnimationState::Run | AnimationState::Idle => player.anim_frame = (player.anim_frame + 1) % max_frames,
+                    AnimationState::Run | AnimationState::Idle | AnimationState::Stunned | AnimationState::Dash => player.anim_frame = (player.anim_frame + 1) % max_frames,
+                    AnimationState::Shield | AnimationState::WallSlide => player.anim_frame = 0,
                     AnimationState::Jump | AnimationState::Hurt => if player.anim_frame + 1 < max_frames { player.anim_frame += 1; } else if player.anim_state == AnimationState::Hurt { player.hit = false; },
                     AnimationState::Shoot => {
-                        // fire the projectile at the right frame
-                        if player.anim_frame == max_frames - 1 && !player.attack_fired {
-                            self.projectiles.push(Projectile::new(player.staff_position(), player.aim * 800.0, player.input_id, Color::WHITE));
-                            player.attack_fired = true;
+                        if player.charging {
+                            // loop the wind-up frames until the button is let go
+                            player.anim_frame = (player.anim_frame + 1) % CHARGE_LOOP_FRAMES.min(max_frames);
+                        } else {
+                            // fire the projectile at the right frame
+                            if player.anim_frame == max_frames - 1 && !player.attack_fired {
+                                self.projectiles.push(Projectile::charged(player.staff_position(), player.aim, player.input_id, Color::WHITE, player.charge_level()));
+                                _data.audio.play(Sfx::Cast);
+                                player.attack_fired = true;
+                                player.charge_time = 0.0;
+                            }
+                            if player.anim_frame + 1 < max_frames { player.anim_frame += 1; } else { player.anim_frame = 0; player.shooting = false; }
                         }
-                        if player.anim_frame + 1 < max_frames { player.anim_frame += 1; } else { player.anim_frame = 0; player.shooting = false; }
                     }
                 }
             }
         }
 
         // move projectiles and check for hits
-        for p in &mut self.projectiles { p.update(dt); }
+        let obstacles: Vec<Rectangle> = self.platforms.iter().filter(|p| p.is_solid()).map(|p| p.rect).chain(self.solids.iter().copied()).collect();
         for p in &mut self.projectiles {
-            if !p.active { continue; }
+            let was_active = p.active;
+            p.update(dt, &obstacles);
+            // fizzled out on a wall - sparks fly back the way it came
+            if was_active && !p.active && !p.exploded {
+                self.particles.burst(&IMPACT, p.pos, (-p.vel.y).atan2(-p.vel.x));
+            }
+        }
+
+        // opposing bolts that meet in mid-air fight it out
+        for i in 0..self.projectiles.len() {
+            let (left, right) = self.projectiles.split_at_mut(i + 1);
+            let a = &mut left[i];
+            for b in right.iter_mut() {
+                if !a.active || !b.active || a.stuck || b.stuck || a.owner_id == b.owner_id { continue; }
+                if a.pos.distance_to(b.pos) <= a.radius + b.radius {
+                    let mid = (a.pos + b.pos) / 2.0;
+                    self.explosions.push(Explosion::new(mid, (a.radius + b.radius) * 1.5));
+                    self.particles.burst(&BLAST, mid, UP);
+                    resolve_clash(a, b);
+                }
+            }
+        }
+
+        // (victim, scorer) - handed out once everything's moved
+        let mut kills: Vec<(usize, usize)> = Vec::new();
+        for p in &mut self.projectiles {
+            // sticky bolts that went off hurt everyone caught in the blast except the caster
+            if p.exploded {
+                self.explosions.push(Explosion::new(p.pos, p.blast_radius()));
+                self.particles.burst(&BLAST, p.pos, UP);
+                for player in &mut self.players {
+                    if p.owner_id == player.input_id || player.is_invincible() { continue; }
+                    if check_collision_circle_rec(p.pos, p.blast_radius(), player.rect()) {
+                        if player.is_shielding() && player.shield_covers(p.pos) {
+                            player.absorb_hit(p.power());
+                        } else {
+                            let hit = Hit::from_bolt(p, player.body_center() - p.pos);
+                            let at = player.body_center();
+                            let killed = damage_player(player, &hit);
+                            if killed { kills.push((player.input_id as usize, hit.attacker_id as usize)); }
+                            self.hitstop_timer = self.hitstop_timer.max(hit.hitstop());
+                            self.camera.add_trauma(if killed { KILL_TRAUMA } else { hit.trauma() });
+                            self.particles.burst(if killed { &DEATH } else { &IMPACT }, at, hit.angle());
+                            _data.audio.play(Sfx::Hit);
+                        }
+                    }
+                }
+                continue;
+            }
+            if !p.active || p.stuck { continue; }
             for player in &mut self.players {
-                if p.owner_id == player.input_id || player.is_invincible() { continue; }
-                if check_collision_circle_rec(p.pos, 10.0, player.rect()) {
+                if p.owner_id == player.input_id { continue; }
+
+                // a well-timed parry sends the bolt back and a success refunds the cooldown
+                if player.is_parrying() && check_collision_circle_rec(p.pos, p.radius + PARRY_REACH, player.rect()) {
+                    p.reflect(player.input_id);
+                    player.parry_cooldown = 0.0;
+                    break;
+                }
+
+                // the raised shield eats bolts coming from the side it faces
+                if player.is_shielding() && player.shield_covers(p.pos)
+                    && (p.pos.distance_to(player.body_center()) <= SHIELD_RADIUS + p.radius || check_collision_circle_rec(p.pos, p.radius, player.rect())) {
                     p.active = false;
-                    player.hp -= 1;
-                    player.i_frame_timer = 1.5;
-                    player.hit = true; 
-                    if player.hp <= 0 {
-                        _data.player_scores[p.owner_id as usize] += 1;
-                        player.hp = 3; 
-                        player.pos = Vector2::new(self.camera.target.x, self.camera.target.y - 400.0); 
-                        player.vel = Vector2::zero();
-                    }
+                    player.absorb_hit(p.power());
+                    break;
+                }
+
+                if player.is_invincible() { continue; }
+                if check_collision_circle_rec(p.pos, p.radius, player.rect()) {
+                    p.active = false;
+                    let hit = Hit::from_bolt(p, p.vel);
+                    let at = player.body_center();
+                    let killed = damage_player(player, &hit);
+                    if killed { kills.push((player.input_id as usize, hit.attacker_id as usize)); }
+                    self.hitstop_timer = self.hitstop_timer.max(hit.hitstop());
+                    self.camera.add_trauma(if killed { KILL_TRAUMA } else { hit.trauma() });
+                    self.particles.burst(if killed { &DEATH } else { &IMPACT }, at, hit.angle());
+                    _data.audio.play(Sfx::Hit);
                 }
             }
         }
         self.projectiles.retain(|p| p.active);
 
+        for e in &mut self.explosions { e.timer -= dt; }
+        self.explosions.retain(|e| e.timer > 0.0);
+
+        // trails behind every bolt in flight, in the caster's colour
+        for p in self.projectiles.iter().filter(|p| !p.stuck) {
+            let color = if p.owner_id == 0 { Color::CYAN } else { Color::ORANGE };
+            self.particles.emit(&particles::tinted(&SPELL_TRAIL, color), p.pos, (-p.vel.y).atan2(-p.vel.x), dt);
+        }
+        // the lava never sits still
+        let lava_surface = Vector2::new(self.camera.target().x, self.lava_y);
+        self.particles.emit(&LAVA_EMBER, lava_surface, UP, dt);
+        self.particles.emit(&LAVA_BUBBLE, lava_surface, UP, dt);
+        self.particles.update(dt);
+
         // screen boundary logic
-        let view_half_width = _data.screen_width as f32 / _data.zoom_factor / 2.0;
-        let left_edge = self.camera.target.x - view_half_width;
-        let right_edge = self.camera.target.x + view_half_width;
+        let (left_edge, right_edge) = self.shaft_edges();
 
+        self.retarget_respawns();
+        let platform_rects: Vec<Rectangle> = self.platforms.iter().map(|p| p.rect).collect();
         for player in &mut self.players {
+            // respawns drift straight down onto their platform, riding along if it moves.
+            // retarget_respawns made sure it's still there and still below
+            if let Some(i) = player.spawn_platform {
+                let plat = &self.platforms[i];
+                player.pos.x += plat.delta.x;
+                player.pos.y += SPAWN_DESCENT_SPEED * dt;
+                if player.pos.y >= plat.rect.y {
+                    player.pos.y = plat.rect.y;
+                    player.finish_spawn(Some(i));
+                    self.particles.burst(&JUMP_DUST, player.pos, UP);
+                }
+                continue;
+            }
+            if player.spawn_protection > 0.0 { player.spawn_protection -= dt; }
             if player.i_frame_timer > 0.0 { player.i_frame_timer -= dt; }
-            player.vel.y += self.gravity * dt;
-            player.pos += player.vel * dt;
-
-            let buffer = 64.0; 
-            if player.pos.x < left_edge + buffer { player.pos.x = left_edge + buffer; player.vel.x = 0.0; }
-            if player.pos.x > right_edge - buffer { player.pos.x = right_edge - buffer; player.vel.x = 0.0; }
-
-            // floor collisions
-            player.grounded = false;
-            for plat in &self.platforms {
-                // only snap to top of platforms if falling down
-                if player.vel.y > 0.0 && player.pos.y >= plat.y && player.pos.y <= plat.y + 30.0 && 
-                   player.pos.x + 23.0 > plat.x && player.pos.x - 23.0 < plat.x + plat.width {
-                    player.pos.y = plat.y;
-                    player.vel.y = 0.0;           
-                    player.grounded = true;       
+            if player.dash_cooldown > 0.0 { player.dash_cooldown -= dt; }
+            if player.wall_jump_lock > 0.0 { player.wall_jump_lock -= dt; }
+            if player.coyote_timer > 0.0 { player.coyote_timer -= dt; }
+            if player.jump_buffer_timer > 0.0 { player.jump_buffer_timer -= dt; }
+            if player.drop_timer > 0.0 { player.drop_timer -= dt; } else { player.drop_platform = None; }
+            if player.in_hitstun() {
+                player.hitstun_timer -= dt;
+                player.vel.x -= player.vel.x * KNOCKBACK_DRAG * dt;
+            }
+            // dashes fly flat, everything else falls
+            if player.is_dashing() {
+                player.dash_timer -= dt;
+                player.vel.y = 0.0;
+            } else {
+                player.vel.y += self.gravity * dt;
+            }
+
+            // sliding down a wall caps the fall speed
+            if player.wall_sliding && player.vel.y > WALL_SLIDE_SPEED { player.vel.y = WALL_SLIDE_SPEED; }
+
+            // move the hitbox through the level - solid blocks stop it from every side,
+            // platforms only catch it from above
+            let start = player.rect();
+            let was_falling = if player.grounded { 0.0 } else { player.vel.y };
+            let platforms = &self.platforms;
+            // the shaft walls are walls to slide down and jump off too. the edges are
+            // measured at `pos`, so shift them over to the hitbox
+            let offset = start.x - player.pos.x;
+            let x_bounds = (left_edge + offset, right_edge + offset + start.width);
+            let moved = move_and_collide(start, player.vel, dt, &self.solids, &platform_rects, x_bounds, |i| {
+                !platforms[i].is_solid() || player.is_dropping_through(i)
+            });
+            player.pos += Vector2::new(moved.rect.x - start.x, moved.rect.y - start.y);
+            player.vel = moved.vel;
+            player.grounded = moved.grounded;
+            player.ground_platform = moved.ground_platform;
+            player.wall_contact = moved.wall;
+            if moved.hit_ceiling { player.jumping = false; }
+
+            if player.grounded {
+                // a proper landing kicks up some dust
+                if was_falling > LANDING_DUST_SPEED {
+                    self.particles.burst(&JUMP_DUST, player.pos, UP);
+                    _data.audio.play(Sfx::Land);
                 }
+                player.on_landed();
+            }
+
+            // what we're standing on changes how we move
+            let ground_kind = player.ground_platform.filter(|_| player.grounded).map(|i| self.platforms[i].kind);
+            player.on_ice = ground_kind == Some(PlatformKind::Ice);
+            if ground_kind == Some(PlatformKind::Bouncy) {
+                player.vel.y = BOUNCE_VELOCITY;
+                player.grounded = false;
+                player.coyote_timer = 0.0;
             }
 
+            player.update_wall_slide();
+
+            // stepping on a crumbling platform starts it falling apart
+            if player.grounded && let Some(i) = player.ground_platform {
+                self.platforms[i].on_stood_on();
+            }
+        }
+
+        // burns come after everyone has moved, respawns still floating down included
+        for player in &mut self.players {
+            // a wizard who's already down can't die again
+            if player.is_down() { continue; }
+
             // lava death check
             if player.pos.y > self.lava_y { 
-                if !player.is_invincible() { player.hp -= 1; player.i_frame_timer = 1.0; player.vel.y = -650.0; player.hit = true; }
-                if player.hp <= 0 || player.pos.y > self.lava_y + 200.0 {
-                    _data.player_scores[if player.input_id == 0 { 1 } else { 0 }] += 1;
-                    player.hp = 3; 
-                    player.pos = Vector2::new(self.camera.target.x, self.camera.target.y - 400.0); 
-                    player.vel = Vector2::zero();
+                if burn_player(player) {
+                    self.camera.add_trauma(BURN_TRAUMA);
+                    _data.audio.play(Sfx::Sizzle);
+                }
+                if player.is_down() || player.pos.y > self.lava_y + 200.0 {
+                    self.particles.burst(&DEATH, Vector2::new(player.pos.x, self.lava_y), UP);
+                    player.hp = 0;
+                    kills.push((player.input_id as usize, rival(player)));
+                    self.camera.add_trauma(KILL_TRAUMA);
+                    continue;
+                }
+            }
+
+            // authored spikes and flames burn just like the lava does
+            let body = player.rect();
+            if self.hazards.iter().any(|h| overlaps(&body, &h.rect)) {
+                if burn_player(player) {
+                    self.camera.add_trauma(BURN_TRAUMA);
+                    _data.audio.play(Sfx::Sizzle);
+                }
+                if player.is_down() {
+                    self.particles.burst(&DEATH, player.body_center(), UP);
+                    kills.push((player.input_id as usize, rival(player)));
+                    self.camera.add_trauma(KILL_TRAUMA);
                 }
             }
         }
+        for (victim, scorer) in kills { self.score_kill(victim, scorer, _data); }
+
+        // keep both wizards in shot - a respawn still floating down doesn't drag the view up
+        let bodies: Vec<Vector2> = self.players.iter().filter(|p| !p.is_spawning()).map(|p| p.body_center()).collect();
+        self.camera.follow(dt, &bodies, self.rooftop_y, self.is_on_rooftop);
         
-        // win condition
-        if _data.player_scores[0] >= 5 || _data.player_scores[1] >= 5 { return SceneSwitch::Push(Box::new(WinScene)); }
+        // win condition - with rounds it's checked once the intermission is over
+        if !self.rules.ends_on_kill && _data.player_scores.iter().any(|s| *s >= _data.score_to_win) {
+            if let Some(recording) = &self.recording { recording.save(); }
+            _data.audio.play(Sfx::Victory);
+            return SceneSwitch::Push(Box::new(WinScene));
+        }
         SceneSwitch::None
     }
 
@@ -270,25 +873,74 @@ impl Scene for GameScene {
         // store time here to avoid borrow checker issues later
         let time = d.get_time();
         
-        // render background
-        if !data.background_tex_vec.is_empty() {
-            d.draw_texture_pro(&data.background_tex_vec[0], 
-                Rectangle::new(0.0,0.0,576.0,324.0), 
-                Rectangle::new(0.0,0.0,d.get_screen_width() as f32, d.get_screen_height() as f32), 
-                Vector2::zero(), 0.0, Color::WHITE);
-        }
+        // render background - layers slide by at their own pace as the camera climbs
+        let camera = self.camera.view();
+        let climb = CAMERA_START_Y - camera.target.y;
+        self.stage.background.draw(d, &data.background_tex_vec, climb, self.climb_progress(), camera.zoom);
 
         {
-            let mut d_cam = d.begin_mode2D(self.camera);
-            
+            let mut d_cam = d.begin_mode2D(camera);
+
+            // shaft walls, for when the camera pulls back far enough to see past the edges
+            let (left_edge, right_edge) = self.shaft_edges();
+            let wall_top = camera.target.y - VIRTUAL_HEIGHT as f32 * 2.0;
+            let wall_height = VIRTUAL_HEIGHT as f32 * 4.0;
+            for wall in [
+                Rectangle::new(left_edge - BODY_WIDTH / 2.0 - 2000.0, wall_top, 2000.0, wall_height),
+                Rectangle::new(right_edge + BODY_WIDTH / 2.0, wall_top, 2000.0, wall_height),
+            ] {
+                d_cam.draw_rectangle_rec(wall, Color::DARKGRAY);
+                d_cam.draw_rectangle_lines_ex(wall, 5.0, Color::BLACK);
+            }
+
             // render platforms
             for (i, plat) in self.platforms.iter().enumerate() {
-                if self.is_on_rooftop && i == self.platforms.len() - 1 {
-                    d_cam.draw_rectangle_rec(*plat, Color::DARKGRAY);
-                    d_cam.draw_rectangle_lines_ex(*plat, 5.0, Color::BLACK);
-                } else {
-                    let tex = &data.obstacle_tex_vec[(i % 3) % data.obstacle_tex_vec.len()];
-                    d_cam.draw_texture_pro(tex, Rectangle::new(0.0,16.0,48.0,15.0), *plat, Vector2::zero(), 0.0, Color::WHITE);
+                if self.is_on_rooftop && i == 0 {
+                    d_cam.draw_rectangle_rec(plat.rect, Color::DARKGRAY);
+                    d_cam.draw_rectangle_lines_ex(plat.rect, 5.0, Color::BLACK);
+                    continue;
+                }
+
+                plat.draw(&mut d_cam, &data.obstacle_tex_vec, &self.stage.tileset, i, time);
+            }
+
+            // solid blocks get the tile texture repeated across them
+            for (i, solid) in self.solids.iter().enumerate() {
+                let tileset = &self.stage.tileset;
+                let tex = &data.obstacle_tex_vec[tileset[(i + 2) % tileset.len()] % data.obstacle_tex_vec.len()];
+                let tile: f32 = 64.0;
+                let mut y = solid.y;
+                while y < solid.y + solid.height {
+                    let h = tile.min(solid.y + solid.height - y);
+                    let mut x = solid.x;
+                    while x < solid.x + solid.width {
+                        let w = tile.min(solid.x + solid.width - x);
+                        d_cam.draw_texture_pro(tex, Rectangle::new(0.0, 0.0, w, h), Rectangle::new(x, y, w, h), Vector2::zero(), 0.0, Color::WHITE);
+                        x += tile;
+                    }
+                    y += tile;
+                }
+                d_cam.draw_rectangle_lines_ex(*solid, 2.0, Color::BLACK.alpha(0.6));
+            }
+
+            // hazards - a row of spikes or a flickering wall of fire
+            for hazard in &self.hazards {
+                let r = hazard.rect;
+                match hazard.kind {
+                    HazardType::Spikes => {
+                        let count = (r.width / 24.0).ceil().max(1.0) as i32;
+                        let w = r.width / count as f32;
+                        for k in 0..count {
+                            let x = r.x + k as f32 * w;
+                            d_cam.draw_triangle(Vector2::new(x + w / 2.0, r.y), Vector2::new(x, r.y + r.height), Vector2::new(x + w, r.y + r.height), Color::GRAY);
+                        }
+                    }
+                    HazardType::Flames => {
+                        let flicker = ((time * 14.0 + r.x as f64).sin() as f32 + 1.0) / 2.0;
+                        let lick = r.height * 0.3 * flicker;
+                        d_cam.draw_rectangle_rec(Rectangle::new(r.x, r.y - lick, r.width, r.height + lick), Color::ORANGE.alpha(0.7));
+                        d_cam.draw_rectangle_rec(Rectangle::new(r.x + r.width * 0.2, r.y + r.height * 0.3, r.width * 0.6, r.height * 0.7), Color::YELLOW.alpha(0.8));
+                    }
                 }
             }
 
@@ -300,8 +952,17 @@ impl Scene for GameScene {
                 } else {
                     (Color::ORANGE, Color::RED.alpha(0.4))
                 };
-                d_cam.draw_circle_v(p.pos, 13.0, glow_color);
-                d_cam.draw_circle_v(p.pos, 8.0, core_color);
+                // stuck bolts flicker faster as the fuse burns down
+                let glow_scale = if p.stuck { 1.3 + ((time * 25.0).sin() as f32 + 1.0) * 0.25 } else { 1.3 };
+                d_cam.draw_circle_v(p.pos, p.radius * glow_scale, glow_color);
+                d_cam.draw_circle_v(p.pos, p.radius * 0.8, core_color);
+            }
+
+            // blast rings from sticky bolts
+            for e in &self.explosions {
+                let t = e.progress();
+                d_cam.draw_circle_v(e.pos, e.radius * t, Color::ORANGE.alpha(0.5 * (1.0 - t)));
+                d_cam.draw_circle_lines(e.pos.x as i32, e.pos.y as i32, e.radius * t, Color::YELLOW.alpha(1.0 - t));
             }
 
             for (index, player) in self.players.iter().enumerate() {
@@ -323,19 +984,55 @@ impl Scene for GameScene {
     
                 d_cam.draw_line_ex(staff_pos, line_end, 6.0, aim_color);
 
+                // a beam of light to float down in, then a bubble for as long as the protection lasts
+                if player.is_spawning() {
+                    let beam_color = if index == 0 { Color::CYAN } else { Color::ORANGE };
+                    let beam_top = camera.target.y - VIRTUAL_HEIGHT as f32;
+                    d_cam.draw_rectangle_rec(Rectangle::new(player.pos.x - 40.0, beam_top, 80.0, player.pos.y - beam_top), beam_color.alpha(0.2));
+                } else if player.spawn_protection > 0.0 {
+                    let center = player.body_center();
+                    d_cam.draw_circle_v(center, player.height * 0.6, Color::WHITE.alpha(0.12));
+                    d_cam.draw_circle_lines(center.x as i32, center.y as i32, player.height * 0.6, Color::WHITE.alpha(0.6));
+                }
 
-                let tex_vec = match (index, player.anim_state) {
-                    (0, AnimationState::Idle) => &data.player1_idle_tex,
-                    (0, AnimationState::Run) => &data.player1_run_tex,
-                    (0, AnimationState::Jump) => &data.player1_jump_tex,
-                    (0, AnimationState::Shoot) => &data.player1_attack_tex,
-                    (0, AnimationState::Hurt) => &data.player1_hurt_tex,
-                    (1, AnimationState::Idle) => &data.player2_idle_tex,
-                    (1, AnimationState::Run) => &data.player2_run_tex,
-                    (1, AnimationState::Jump) => &data.player2_jump_tex,
-                    (1, AnimationState::Shoot) => &data.player2_attack_tex,
-                    _ => &data.player2_hurt_tex,
-                };
+                // bright ring while the parry window is open
+                if player.is_parrying() {
+                    let center = player.body_center();
+                    d_cam.draw_circle_lines(center.x as i32, center.y as i32, player.height * 0.55, Color::WHITE);
+                    d_cam.draw_circle_v(center, player.height * 0.55, Color::WHITE.alpha(0.15));
+                }
+
+                // shield arc in the aimed direction, reddening as it wears down
+                if player.is_shielding() {
+                    let center = player.body_center();
+                    let aim_deg = player.aim.y.atan2(player.aim.x).to_degrees();
+                    let wear = 1.0 - player.shield_hp / SHIELD_MAX;
+                    let base = if index == 0 { Color::SKYBLUE } else { Color::GOLD };
+                    let shield_color = base.lerp(Color::RED, wear);
+                    d_cam.draw_ring(center, SHIELD_RADIUS - 8.0, SHIELD_RADIUS, aim_deg - 70.0, aim_deg + 70.0, 24, shield_color.alpha(0.75));
+                }
+
+                // dizzy stars circling a wizard whose shield just shattered
+                if player.is_stunned() {
+                    for star in 0..3 {
+                        let angle = time as f32 * 6.0 + star as f32 * 2.1;
+                        let star_pos = Vector2::new(player.pos.x + angle.cos() * 30.0, player.pos.y - player.height * 0.9 + angle.sin() * 8.0);
+                        d_cam.draw_circle_v(star_pos, 6.0, Color::YELLOW);
+                    }
+                }
+
+                // charge aura swells with each level and pulses while held
+                if player.charging {
+                    let level = player.charge_level() as f32;
+                    let pulse = ((time * 12.0).sin() as f32 + 1.0) / 2.0;
+                    let aura_radius = 14.0 + level * 8.0 + pulse * 4.0;
+                    let aura_color = if index == 0 { Color::CYAN } else { Color::ORANGE };
+                    d_cam.draw_circle_v(staff_pos, aura_radius, aura_color.alpha(0.15 + 0.1 * level));
+                    d_cam.draw_circle_lines(staff_pos.x as i32, staff_pos.y as i32, aura_radius, aura_color.alpha(0.8));
+                }
+
+
+                let tex_vec = anim_textures(data, index, player.anim_state);
 
                 // draw the wizard
                 if !tex_vec.is_empty() {
@@ -349,6 +1046,16 @@ impl Scene for GameScene {
                     d_cam.draw_texture_pro(texture, source, dest, Vector2::new((texture.width() as f32 * scale)/2.0, texture.height() as f32 * scale), 0.0, tint);
                 }
                 
+                // shield meter under the hearts, only while it isn't full
+                if player.shield_hp < SHIELD_MAX {
+                    let bar_w = 80.0;
+                    let bar = Rectangle::new(player.pos.x - bar_w / 2.0, player.pos.y - 110.0, bar_w, 8.0);
+                    let fill = Rectangle::new(bar.x, bar.y, bar_w * (player.shield_hp / SHIELD_MAX), bar.height);
+                    let fill_color = if player.is_stunned() { Color::RED } else { Color::SKYBLUE };
+                    d_cam.draw_rectangle_rec(bar, Color::BLACK.alpha(0.5));
+                    d_cam.draw_rectangle_rec(fill, fill_color);
+                }
+
                 // hp heart icons
                 if !data.ui_assets_tex_vec.is_empty() {
                     let heart_tex = &data.ui_assets_tex_vec[0];
@@ -371,20 +1078,145 @@ impl Scene for GameScene {
             let lava_h = data.lava_tex.height() as f32;
             let left_x = -3000.0;
             let right_x = 3000.0;
-            let bottom_y = self.camera.target.y + 3000.0;
+            let bottom_y = camera.target.y + 3000.0;
             let cols = ((right_x - left_x) / (lava_w - 0.5)).ceil() as i32;
             for col in 0..cols {
                 let x = left_x + col as f32 * (lava_w - 0.5);
                 d_cam.draw_texture_pro(&data.lava_tex, Rectangle::new(0.0, 0.0, lava_w, lava_h / 2.0), Rectangle::new(x, self.lava_y, lava_w, lava_h / 2.0), Vector2::zero(), 0.0, Color::WHITE);
             }
             d_cam.draw_texture_pro(&data.lava_tex, Rectangle::new(0.0, lava_h / 2.0, lava_w, lava_h / 2.0), Rectangle::new(left_x, self.lava_y + lava_h / 2.0, right_x - left_x, bottom_y - self.lava_y), Vector2::zero(), 0.0, Color::WHITE);
-            if self.is_on_rooftop { d_cam.draw_text("FINAL DUEL!", 750, (self.rooftop_y - 200.0) as i32, 60, Color::BLACK); }
+            // sparks, embers and dust go over everything, lava included
+            self.particles.draw(&mut d_cam);
+
+            if self.is_on_rooftop {
+                let banner_w = d_cam.measure_text("FINAL DUEL!", 60);
+                d_cam.draw_text("FINAL DUEL!", self.camera.target().x as i32 - banner_w / 2, (self.rooftop_y - 200.0) as i32, 60, Color::BLACK);
+            }
         } 
         
         // ui overlay
         d.draw_text(&format!("P1 Score: {}", data.player_scores[0]), 20, 20, 30, Color::ORANGE);
-        d.draw_text(&format!("P2 Score: {}", data.player_scores[1]), d.get_screen_width() - 250, 20, 30, Color::PURPLE);
+        let p2_score = format!("P2 Score: {}", data.player_scores[1]);
+        let p2_w = d.measure_text(&p2_score, 30);
+        d.draw_text(&p2_score, VIRTUAL_WIDTH - 20 - p2_w, 20, 30, Color::PURPLE);
+        d.draw_text(&self.level.name, 20, VIRTUAL_HEIGHT - 40, 20, Color::DARKGRAY);
+
+        // 3-2-1-DUEL over the middle of the screen, with the round number above it
+        let center_x = VIRTUAL_WIDTH / 2;
+        let center_y = VIRTUAL_HEIGHT / 2;
+        if let Some(text) = self.round.countdown_text(&self.rules) {
+            if self.rules.ends_on_kill {
+                let round_text = format!("ROUND {}", self.round.number);
+                let round_w = d.measure_text(&round_text, 40);
+                d.draw_text(&round_text, center_x - round_w / 2, center_y - 140, 40, Color::BLACK);
+            }
+            let text_w = d.measure_text(&text, 120);
+            d.draw_text(&text, center_x - text_w / 2 + 4, center_y - 56, 120, Color::BLACK);
+            d.draw_text(&text, center_x - text_w / 2, center_y - 60, 120, Color::WHITE);
+        }
+
+        // who took the round, over the finishing blow and the intermission
+        if let RoundPhase::Over { scorer } = self.round.phase {
+            let (text, color) = if scorer == 0 { ("PLAYER 1 SCORES!", Color::ORANGE) } else { ("PLAYER 2 SCORES!", Color::PURPLE) };
+            d.draw_rectangle(0, center_y - 90, VIRTUAL_WIDTH, 180, Color::BLACK.alpha(0.6));
+            let text_w = d.measure_text(text, 80);
+            d.draw_text(text, center_x - text_w / 2, center_y - 60, 80, color);
+            let score_text = format!("{} - {}", data.player_scores[0], data.player_scores[1]);
+            let score_w = d.measure_text(&score_text, 30);
+            d.draw_text(&score_text, center_x - score_w / 2, center_y + 35, 30, Color::WHITE);
+        }
     }
 
     fn on_exit(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) {}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::SPAWN_PROTECTION_TIME;
+    use crate::tower_gen;
+
+    // the starting screen of a generated tower with just these platforms on it
+    fn arena(platforms: Vec<Platform>) -> GameScene {
+        let stage = stage::courtyard();
+        let mut game = GameScene::from_level(tower_gen::generate(1, &stage), stage);
+        game.platforms = platforms;
+        game.solids.clear();
+        game.hazards.clear();
+        game
+    }
+
+    fn plat(x: f32, y: f32, kind: PlatformKind) -> Platform {
+        Platform::new(Rectangle::new(x, y, 200.0, 20.0), kind)
+    }
+
+    #[test]
+    fn respawn_keeps_its_distance_from_the_opponent() {
+        let mut game = arena(vec![plat(800.0, 300.0, PlatformKind::Static), plat(1500.0, 600.0, PlatformKind::Static)]);
+        game.players[1].pos = Vector2::new(900.0, 300.0);
+
+        // the higher platform is right next to them, so the lower one wins
+        let (spot, platform) = game.safe_respawn(0);
+        assert_eq!(platform, Some(1));
+        assert_eq!(spot, Vector2::new(1650.0, 600.0));
+        assert!(spot.distance_to(game.players[1].pos) >= SPAWN_MIN_DISTANCE);
+    }
+
+    #[test]
+    fn respawn_skips_crumbling_bouncy_and_burning_platforms() {
+        let mut game = arena(vec![
+            plat(1200.0, 200.0, PlatformKind::Crumbling),
+            plat(1200.0, 300.0, PlatformKind::Bouncy),
+            plat(1200.0, 400.0, PlatformKind::Static),
+            plat(1200.0, 600.0, PlatformKind::Static),
+        ]);
+        game.hazards.push(Hazard { kind: HazardType::Spikes, rect: Rectangle::new(1200.0, 360.0, 200.0, 40.0) });
+        game.players[1].pos = Vector2::new(100.0, 700.0);
+
+        assert_eq!(game.safe_respawn(0).1, Some(3));
+    }
+
+    #[test]
+    fn respawn_falls_back_to_the_furthest_spot_then_to_dropping_in() {
+        // everything's too close - take what's furthest away
+        let mut game = arena(vec![plat(800.0, 300.0, PlatformKind::Static), plat(600.0, 400.0, PlatformKind::Static)]);
+        game.players[1].pos = Vector2::new(900.0, 300.0);
+        assert_eq!(game.safe_respawn(0), (Vector2::new(650.0, 400.0), Some(1)));
+
+        // nothing to stand on at all
+        game.platforms.clear();
+        let (spot, platform) = game.safe_respawn(0);
+        assert_eq!(platform, None);
+        assert_eq!(spot, Vector2::new(game.camera.target().x, game.camera.scroll_y - 400.0));
+    }
+
+    #[test]
+    fn respawn_reaims_when_its_platform_is_gone() {
+        let mut game = arena(vec![plat(1000.0, 400.0, PlatformKind::Static), plat(1400.0, 600.0, PlatformKind::Static)]);
+        game.players[1].pos = Vector2::new(100.0, 700.0);
+        game.players[0].respawn(Vector2::new(1100.0, 200.0), Some(0));
+
+        // still there - nothing changes
+        game.retarget_respawns();
+        assert_eq!(game.players[0].spawn_platform, Some(0));
+        assert_eq!(game.players[0].pos.x, 1100.0);
+
+        game.platforms[0].crumble = CrumbleState::Gone(1.0);
+        game.retarget_respawns();
+        assert_eq!(game.players[0].spawn_platform, Some(1));
+        assert_eq!(game.players[0].pos.x, 1550.0);
+    }
+
+    #[test]
+    fn respawn_ends_when_nothing_is_left_below() {
+        let mut game = arena(vec![plat(1000.0, 400.0, PlatformKind::Static), plat(1400.0, 300.0, PlatformKind::Static)]);
+        game.players[1].pos = Vector2::new(100.0, 700.0);
+        game.players[0].respawn(Vector2::new(1100.0, 350.0), Some(0));
+
+        // swallowed by the lava, and the only other platform is above
+        game.lava_y = 380.0;
+        game.retarget_respawns();
+        assert!(!game.players[0].is_spawning());
+        assert_eq!(game.players[0].spawn_protection, SPAWN_PROTECTION_TIME);
+    }
+}
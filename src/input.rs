@@ -0,0 +1,95 @@
+//! What each wizard is told to do in a frame.
+//!
+//! The match doesn't read the keyboard or gamepads itself. Every frame each wizard gets a
+//! [`PlayerInput`], either read from a person's controls, made up by the CPU (see `cpu.rs`)
+//! or played back from a recording (see `replay.rs`). Keeping it in one small struct is what
+//! lets a replay reproduce a match exactly.
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{Action, KeyBindings};
+
+// sticks and triggers have to move this far before they count
+const STICK_DEADZONE: f32 = 0.1;
+const TRIGGER_THRESHOLD: f32 = 0.5;
+const STICK_DOWN_THRESHOLD: f32 = 0.5;
+
+/// Who controls a wizard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pilot {
+    #[default]
+    Human,
+    Cpu,
+}
+
+/// One wizard's controls for one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerInput {
+    /// -1 (left) to 1 (right).
+    pub direction: f32,
+    pub jump_pressed: bool,
+    pub jump_held: bool,
+    pub drop_held: bool,
+    pub dash_pressed: bool,
+    pub shield_held: bool,
+    pub cast_held: bool,
+    pub parry_pressed: bool,
+    /// Which way to aim, if the wizard is aiming anywhere new this frame.
+    pub aim: Option<(f32, f32)>,
+}
+
+impl PlayerInput {
+    pub fn aim(&self) -> Option<Vector2> {
+        self.aim.map(|(x, y)| Vector2::new(x, y))
+    }
+}
+
+/// Reads a person's gamepad and bound keys. `mouse_aim` is where the mouse points in the
+/// world, relative to the wizard, for the player who aims with it.
+pub fn read_human(rl: &RaylibHandle, keys: &KeyBindings, pad: i32, mouse_aim: Option<Vector2>) -> PlayerInput {
+    let mut input = PlayerInput::default();
+
+    // controller support
+    if rl.is_gamepad_available(pad) {
+        let axis_x = rl.get_gamepad_axis_movement(pad, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
+        if axis_x.abs() > STICK_DEADZONE { input.direction = axis_x; }
+
+        input.jump_pressed = rl.is_gamepad_button_pressed(pad, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+        input.jump_held = rl.is_gamepad_button_down(pad, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+        input.dash_pressed = rl.is_gamepad_button_pressed(pad, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT);
+        input.drop_held = rl.get_gamepad_axis_movement(pad, GamepadAxis::GAMEPAD_AXIS_LEFT_Y) > STICK_DOWN_THRESHOLD
+            || rl.is_gamepad_button_down(pad, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN);
+        input.shield_held = rl.get_gamepad_axis_movement(pad, GamepadAxis::GAMEPAD_AXIS_LEFT_TRIGGER) > TRIGGER_THRESHOLD;
+        input.cast_held = rl.get_gamepad_axis_movement(pad, GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER) > TRIGGER_THRESHOLD;
+        input.parry_pressed = rl.is_gamepad_button_pressed(pad, GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1);
+        let stick = Vector2::new(
+            rl.get_gamepad_axis_movement(pad, GamepadAxis::GAMEPAD_AXIS_RIGHT_X),
+            rl.get_gamepad_axis_movement(pad, GamepadAxis::GAMEPAD_AXIS_RIGHT_Y),
+        );
+        if stick.length() > STICK_DEADZONE {
+            let aim = stick.normalized();
+            input.aim = Some((aim.x, aim.y));
+        }
+    }
+
+    // keyboard and mouse, as bound in the settings
+    if keys.down(rl, Action::Left) { input.direction = -1.0; }
+    if keys.down(rl, Action::Right) { input.direction = 1.0; }
+    input.jump_pressed |= keys.pressed(rl, Action::Jump);
+    input.jump_held |= keys.down(rl, Action::Jump);
+    input.dash_pressed |= keys.pressed(rl, Action::Dash);
+    input.drop_held |= keys.down(rl, Action::Drop);
+    input.shield_held |= keys.down(rl, Action::Shield);
+    input.cast_held |= keys.down(rl, Action::Cast);
+    input.parry_pressed |= keys.pressed(rl, Action::Parry);
+
+    // the mouse only aims when there's no gamepad to do it
+    if let Some(diff) = mouse_aim
+        && !rl.is_gamepad_available(pad)
+        && diff.length() > 0.0 {
+        let aim = diff.normalized();
+        input.aim = Some((aim.x, aim.y));
+    }
+    input
+}
@@ -0,0 +1,278 @@
+//! Authored tower levels.
+//!
+//! A level is a JSON file listing the platforms, solid blocks and hazards of the climb,
+//! where the wizards start, and the layout of the rooftop arena. `GameScene` builds itself
+//! from a `LevelFile`, either loaded from disk or built by `tower_gen`.
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::platform::{Platform, PlatformKind};
+use crate::stage::Stage;
+use crate::tower_gen;
+
+/// Where the game looks for a hand-authored tower before falling back to the generator.
+pub const TOWER_LEVEL_PATH: &str = "./resources/levels/tower.json";
+
+/// Every platform is this thick; only the width is authored.
+pub const PLATFORM_HEIGHT: f32 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlatformType {
+    #[default]
+    Static,
+    MovingHorizontal { range: f32, speed: f32 },
+    MovingVertical { range: f32, speed: f32 },
+    Crumbling,
+    Bouncy,
+    Ice,
+}
+
+impl PlatformType {
+    pub fn to_kind(self) -> PlatformKind {
+        match self {
+            PlatformType::Static => PlatformKind::Static,
+            PlatformType::MovingHorizontal { range, speed } => PlatformKind::Moving { axis: Vector2::new(1.0, 0.0), range, speed },
+            PlatformType::MovingVertical { range, speed } => PlatformKind::Moving { axis: Vector2::new(0.0, 1.0), range, speed },
+            PlatformType::Crumbling => PlatformKind::Crumbling,
+            PlatformType::Bouncy => PlatformKind::Bouncy,
+            PlatformType::Ice => PlatformKind::Ice,
+        }
+    }
+
+    pub fn from_kind(kind: PlatformKind) -> Self {
+        match kind {
+            PlatformKind::Static => PlatformType::Static,
+            PlatformKind::Moving { axis, range, speed } if axis.x.abs() >= axis.y.abs() => PlatformType::MovingHorizontal { range, speed },
+            PlatformKind::Moving { range, speed, .. } => PlatformType::MovingVertical { range, speed },
+            PlatformKind::Crumbling => PlatformType::Crumbling,
+            PlatformKind::Bouncy => PlatformType::Bouncy,
+            PlatformKind::Ice => PlatformType::Ice,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HazardType {
+    Spikes,
+    Flames,
+}
+
+/// A landable platform segment. `texture` indexes into `GameData::obstacle_tex_vec`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlatformDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    #[serde(default)]
+    pub kind: PlatformType,
+    #[serde(default)]
+    pub texture: Option<usize>,
+}
+
+impl PlatformDef {
+    pub fn build(&self, offset_y: f32) -> Platform {
+        let rect = Rectangle::new(self.x, self.y + offset_y, self.width, PLATFORM_HEIGHT);
+        let mut platform = Platform::new(rect, self.kind.to_kind());
+        platform.texture = self.texture;
+        platform
+    }
+}
+
+/// A solid block that stops players from every side.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BlockDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl BlockDef {
+    pub fn build(&self, offset_y: f32) -> Rectangle {
+        Rectangle::new(self.x, self.y + offset_y, self.width, self.height)
+    }
+}
+
+/// A zone that burns anyone who touches it, same as the lava.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HazardDef {
+    pub kind: HazardType,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl HazardDef {
+    pub fn build(&self, offset_y: f32) -> Hazard {
+        Hazard { kind: self.kind, rect: Rectangle::new(self.x, self.y + offset_y, self.width, self.height) }
+    }
+}
+
+/// A hazard placed in the world.
+#[derive(Debug, Clone, Copy)]
+pub struct Hazard {
+    pub kind: HazardType,
+    pub rect: Rectangle,
+}
+
+/// Feet position for a wizard, one per player.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl SpawnPoint {
+    pub fn to_vec(self) -> Vector2 {
+        Vector2::new(self.x, self.y)
+    }
+}
+
+/// The final duel. Coordinates are relative to `LevelFile::rooftop_y`, and a wide floor
+/// is always added `floor_offset` below it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArenaLayout {
+    #[serde(default = "default_floor_offset")]
+    pub floor_offset: f32,
+    #[serde(default)]
+    pub platforms: Vec<PlatformDef>,
+    #[serde(default)]
+    pub solids: Vec<BlockDef>,
+    #[serde(default)]
+    pub hazards: Vec<HazardDef>,
+    #[serde(default)]
+    pub spawn_points: Vec<SpawnPoint>,
+}
+
+fn default_floor_offset() -> f32 {
+    400.0
+}
+
+/// What happens to tower pieces once the lava passes them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecycleMode {
+    /// The authored tower covers the whole climb; nothing is reused.
+    None,
+    /// Pieces jump up by `tower_height`, so the authored section repeats exactly.
+    Repeat,
+    /// Pieces jump up by `tower_height` and get a new position from a hash (the original generator).
+    Reshuffle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelFile {
+    pub name: String,
+    /// Set on generated towers so players can share them.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub rooftop_y: f32,
+    pub recycle: RecycleMode,
+    #[serde(default)]
+    pub tower_height: f32,
+    pub spawn_points: Vec<SpawnPoint>,
+    pub platforms: Vec<PlatformDef>,
+    #[serde(default)]
+    pub solids: Vec<BlockDef>,
+    #[serde(default)]
+    pub hazards: Vec<HazardDef>,
+    pub rooftop: ArenaLayout,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    /// Parsed fine but can't be played.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(e) => write!(f, "couldn't read level file: {e}"),
+            LevelError::Parse(e) => write!(f, "level file is malformed: {e}"),
+            LevelError::Invalid(why) => write!(f, "level file can't be used: {why}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(e: std::io::Error) -> Self {
+        LevelError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for LevelError {
+    fn from(e: serde_json::Error) -> Self {
+        LevelError::Parse(e)
+    }
+}
+
+impl LevelFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LevelError> {
+        let text = std::fs::read_to_string(path)?;
+        let level: LevelFile = serde_json::from_str(&text)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    /// Catches settings serde can't, like a repeating tower with nothing to repeat.
+    pub fn validate(&self) -> Result<(), LevelError> {
+        if self.recycle != RecycleMode::None && self.tower_height <= 0.0 {
+            return Err(LevelError::Invalid("a recycling tower needs a tower_height above 0"));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LevelError> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The authored tower if there is one, otherwise one generated from `seed` for `stage`.
+    pub fn load_or_generate(path: impl AsRef<Path>, seed: u64, stage: &Stage) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return tower_gen::generate(seed, stage);
+        }
+        Self::load(path).unwrap_or_else(|e| {
+            tracing::warn!("{}: {e}, using a generated tower", path.display());
+            tower_gen::generate(seed, stage)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stage;
+
+    #[test]
+    fn example_tower_loads() {
+        let level = LevelFile::load("./resources/levels/example_tower.json").expect("example tower should load");
+        assert!(level.validate().is_ok());
+        assert_eq!(level.recycle, RecycleMode::Repeat);
+        assert!(!level.platforms.is_empty());
+    }
+
+    #[test]
+    fn recycling_needs_a_tower_height() {
+        for recycle in [RecycleMode::Repeat, RecycleMode::Reshuffle] {
+            let level = LevelFile { recycle, tower_height: 0.0, ..tower_gen::generate(7, &stage::courtyard()) };
+            assert!(matches!(level.validate(), Err(LevelError::Invalid(_))));
+        }
+        // generated towers are tall enough to not recycle at all
+        assert!(tower_gen::generate(7, &stage::courtyard()).validate().is_ok());
+    }
+}
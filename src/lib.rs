@@ -2,10 +2,30 @@
 //! 
 //! 
 pub mod game_data;
+pub mod audio;
 pub mod scenes;
 pub mod game_scene;
 pub mod menu_scene;
+pub mod pause_scene;
 pub mod utils;
 pub mod player;
 pub mod projectile;
-pub mod win_scene;
\ No newline at end of file
+pub mod win_scene;
+pub mod collision;
+pub mod camera;
+pub mod canvas;
+pub mod platform;
+pub mod level;
+pub mod tower_gen;
+pub mod stage;
+pub mod parallax;
+pub mod particles;
+pub mod editor_scene;
+pub mod stage_select_scene;
+pub mod settings;
+pub mod settings_scene;
+pub mod cli;
+pub mod rounds;
+pub mod input;
+pub mod cpu;
+pub mod replay;
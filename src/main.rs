@@ -1,29 +1,72 @@
 use raylib::prelude::*;
+use raylib_framework_testing::audio::AudioManager;
+use raylib_framework_testing::cli::{self, CliError};
+use raylib_framework_testing::canvas::{Letterbox, VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
 use raylib_framework_testing::game_data::GameData;
+use raylib_framework_testing::game_scene::GameScene;
+use raylib_framework_testing::level::{LevelFile, TOWER_LEVEL_PATH};
 use raylib_framework_testing::menu_scene::MenuScene;
+use raylib_framework_testing::replay::Replay;
 use raylib_framework_testing::scenes::{Scene, SceneSwitch};
+use raylib_framework_testing::settings::{DisplayMode, Settings, WINDOWED_HEIGHT, WINDOWED_WIDTH};
+use raylib_framework_testing::stage;
 
 use std::fs::OpenOptions;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing_subscriber::prelude::*;
 
+// does whatever the top scene asked for with the stack
+fn apply_switch(scenes: &mut Vec<Box<dyn Scene>>, switch: SceneSwitch, keep_playing: &mut bool) {
+    match switch {
+        SceneSwitch::Push(new_scene) => scenes.push(new_scene),
+        SceneSwitch::Replace(new_scene) => {
+            scenes.pop();
+            scenes.push(new_scene);
+        },
+        SceneSwitch::Pop => { scenes.pop(); },
+        SceneSwitch::PopThen(then) => {
+            scenes.pop();
+            apply_switch(scenes, *then, keep_playing);
+        },
+        SceneSwitch::Quit => *keep_playing = false,
+        SceneSwitch::None => ()
+    }
+    // nothing left to show
+    if scenes.is_empty() { *keep_playing = false; }
+}
+
 fn main() {
-    // window and monitor setup
-    let base_height: i32 = 1080;
+    // launch flags - run with --help to see them
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(CliError::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    // a replay that can't be read is worth hearing about before the window opens
+    let replay = options.replay.as_ref().map(|path| Replay::load(path).unwrap_or_else(|e| {
+        eprintln!("{}: {e}", path.display());
+        std::process::exit(1);
+    }));
 
+    // window setup - the game itself always draws at 1920x1080 and gets scaled to fit
     let (mut rl, thread) = raylib::init()
+        .size(WINDOWED_WIDTH, WINDOWED_HEIGHT)
         .resizable() 
         .title("Wizard Duel")
         .build();
 
-    rl.maximize_window();
-    rl.toggle_fullscreen();
+    // escape pauses matches instead of closing the window
+    rl.set_exit_key(None);
 
-    // scaling everything based on the monitor height
-    let monitor_w = rl.get_screen_width(); 
-    let monitor_h = rl.get_screen_height();
-    let zoom_factor = (monitor_h as f32) / (base_height as f32);
+    let mut canvas = rl.load_render_texture(&thread, VIRTUAL_WIDTH as u32, VIRTUAL_HEIGHT as u32).unwrap();
+    canvas.set_texture_filter(&thread, TextureFilter::TEXTURE_FILTER_BILINEAR);
 
     // debug logging to a file so we can see what's breaking
     let stdout_log = tracing_subscriber::fmt::layer().pretty();
@@ -38,10 +81,17 @@ fn main() {
         .with_writer(Arc::new(file));
 
     tracing_subscriber::Registry::default()
+        .with(options.log_level)
         .with(stdout_log)
         .with(debug_log)
         .init();
 
+    // saved display mode and frame pacing. flags win over the saved display settings
+    // for this run, without being saved
+    let settings = Settings::load();
+    options.display_settings(&settings).apply_display(&mut rl);
+    settings.apply_frame_pacing(&mut rl);
+
     // loading all the player 1 stuff
     let run1_vec = vec![
         rl.load_texture(&thread, "./resources/p1_RUN_000.png").unwrap(),
@@ -107,25 +157,51 @@ fn main() {
     ];
 
     // loading maps and ui bits
-    let background_vec = vec![rl.load_texture(&thread, "./resources/background 1.png").unwrap()];
+    let background_vec = vec![
+        rl.load_texture(&thread, "./resources/background 1.png").unwrap(),
+        rl.load_texture(&thread, "./resources/background 3.png").unwrap(),
+    ];
     let obstacle_vec = vec![
         rl.load_texture(&thread, "./resources/tile1.png").unwrap(),
         rl.load_texture(&thread, "./resources/tile2.png").unwrap(),
         rl.load_texture(&thread, "./resources/tile3.png").unwrap(),
+        rl.load_texture(&thread, "./resources/tile4.png").unwrap(),
     ];
     let ui_vec = vec![rl.load_texture(&thread, "./resources/heart.png").unwrap()];
     let lava_tex = rl.load_texture(&thread, "./resources/lava.png").unwrap();
 
     // dumping all the assets into the game state
+    // sound - falls back to silence if there's no audio device
+    let mut audio = AudioManager::init();
+    settings.apply_audio(&mut audio);
+
     let mut game_data = GameData::new(
-        monitor_w, monitor_h, zoom_factor, 
+        audio,
+        settings,
         run1_vec, idle1_vec, jump1_vec, hurt1_vec, attack1_vec,
         run2_vec, idle2_vec, jump2_vec, hurt2_vec, attack2_vec,
         background_vec, obstacle_vec, ui_vec, lava_tex
     );
 
-    // starting at the menu
+    if let Some(seed) = options.seed { game_data.tower_seed = seed; }
+    if let Some(score) = options.score_to_win { game_data.score_to_win = score; }
+
+    // starting at the menu, or already climbing with the menu waiting underneath
     let mut scenes: Vec<Box<dyn Scene>> = vec![Box::new(MenuScene)];
+    if let Some(replay) = replay {
+        game_data.score_to_win = replay.score_to_win;
+        scenes.push(Box::new(GameScene::replaying(replay)));
+    } else if options.skip_menu || options.record.is_some() {
+        let stage = options.stage.clone().unwrap_or_else(stage::courtyard);
+        let level = LevelFile::load_or_generate(TOWER_LEVEL_PATH, game_data.tower_seed, &stage);
+        let game = GameScene::from_level(level, stage);
+        let game = match &options.record {
+            Some(path) => game.recording_to(path.clone(), game_data.score_to_win),
+            None => game,
+        };
+        scenes.push(Box::new(game));
+    }
+    game_data.launch = options;
     let mut last_time = Instant::now();
     let mut keep_playing = true;
      
@@ -135,32 +211,51 @@ fn main() {
         let delta = (temp - last_time).as_secs_f32();
         last_time = temp;
 
+        // F11 or alt+enter flips between fullscreen and a window from anywhere, and remembers it
+        let alt = rl.is_key_down(KeyboardKey::KEY_LEFT_ALT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_ALT);
+        if rl.is_key_pressed(KeyboardKey::KEY_F11) || (alt && rl.is_key_pressed(KeyboardKey::KEY_ENTER)) {
+            // flips whatever's on screen, which takes over from --windowed
+            let shown = game_data.launch.display_settings(&game_data.settings).display;
+            game_data.settings.display = match shown {
+                DisplayMode::Windowed => DisplayMode::Fullscreen,
+                DisplayMode::Borderless | DisplayMode::Fullscreen => DisplayMode::Windowed,
+            };
+            game_data.launch.windowed = false;
+            game_data.apply_display(&mut rl);
+            if let Err(e) = game_data.settings.save() { tracing::warn!("{e}"); }
+        }
+
+        // keep the music streaming
+        game_data.audio.update(delta);
+
+        // refit every frame so resizes and fullscreen flips take effect straight away,
+        // and keep the mouse reporting canvas coordinates
+        let letterbox = Letterbox::fit(rl.get_screen_width(), rl.get_screen_height());
+        letterbox.apply_to_mouse(&mut rl);
+
         // handle inputs and scene swapping
         let result = scenes.last_mut().unwrap().handle_input(&mut rl, &mut game_data);
-        match result {
-            SceneSwitch::Push(new_scene) => scenes.push(new_scene),
-            SceneSwitch::Replace(new_scene) => {
-                scenes.pop();
-                scenes.push(new_scene);
-            },
-            SceneSwitch::Quit => keep_playing = false,
-            _ => ()
-        }
+        apply_switch(&mut scenes, result, &mut keep_playing);
+        if !keep_playing { break; }
 
         // update game logic
         let result = scenes.last_mut().unwrap().update(delta, &mut game_data);
-        match result {
-            SceneSwitch::Push(new_scene) => scenes.push(new_scene),
-            SceneSwitch::Replace(new_scene) => {
-                scenes.pop();
-                scenes.push(new_scene);
-            },
-            SceneSwitch::Quit => keep_playing = false,
-            _ => ()
-        }
+        apply_switch(&mut scenes, result, &mut keep_playing);
+        if !keep_playing { break; }
 
-        // draw everything
+        // draw the scene onto the canvas, then the canvas into the window with black bars
         let mut d = rl.begin_drawing(&thread); 
-        scenes.last().unwrap().draw(&mut d, &mut game_data);
+        {
+            let mut c = d.begin_texture_mode(&thread, &mut canvas);
+            // start from the topmost scene that isn't an overlay and paint upwards
+            let first = scenes.iter().rposition(|s| !s.is_overlay()).unwrap_or(0);
+            for scene in &scenes[first..] {
+                scene.draw(&mut c, &mut game_data);
+            }
+        }
+        d.clear_background(Color::BLACK);
+        // render textures come out upside down, hence the negative height
+        let source = Rectangle::new(0.0, 0.0, VIRTUAL_WIDTH as f32, -(VIRTUAL_HEIGHT as f32));
+        d.draw_texture_pro(&canvas, source, letterbox.dest(), Vector2::zero(), 0.0, Color::WHITE);
     }
 }
\ No newline at end of file
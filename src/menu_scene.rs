@@ -1,7 +1,13 @@
 use raylib::prelude::*;
+use crate::audio::MENU_MUSIC;
+use crate::canvas::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
 use crate::game_data::GameData;
-use crate::game_scene::GameScene;
+use crate::editor_scene::LevelEditorScene;
+use crate::level::{LevelFile, TOWER_LEVEL_PATH};
 use crate::scenes::{Scene, SceneSwitch};
+use crate::stage;
+use crate::settings_scene::SettingsScene;
+use crate::stage_select_scene::StageSelectScene;
 
 pub struct MenuScene;
 
@@ -13,19 +19,40 @@ impl Scene for MenuScene {
         if rl.is_key_pressed(KeyboardKey::KEY_ENTER)
             || rl.is_gamepad_button_pressed(0, GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT)
         {
-            // reset scores for the fresh run
-            data.player_scores = vec![0, 0];
-            return SceneSwitch::Push(Box::new(GameScene::new(
-                0,
-                data.screen_width,
-                data.screen_height,
-                data.zoom_factor,
+            // pick where to climb first
+            return SceneSwitch::Push(Box::new(StageSelectScene::new()));
+        }
+
+        // E opens the level editor on the tower file
+        if rl.is_key_pressed(KeyboardKey::KEY_E) {
+            let stage = stage::courtyard();
+            let level = LevelFile::load_or_generate(TOWER_LEVEL_PATH, data.tower_seed, &stage);
+            return SceneSwitch::Push(Box::new(LevelEditorScene::new(
+                level,
+                stage,
+                TOWER_LEVEL_PATH,
             )));
         }
+
+        // S for display, sound and controls
+        if rl.is_key_pressed(KeyboardKey::KEY_S) {
+            return SceneSwitch::Push(Box::new(SettingsScene::new()));
+        }
+
+        // escape no longer closes the window by itself, so the title screen does it
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneSwitch::Quit;
+        }
+
+        // R rolls a new tower - share the seed and a friend climbs the same one
+        if rl.is_key_pressed(KeyboardKey::KEY_R) {
+            data.tower_seed = rand::random();
+        }
         SceneSwitch::None
     }
 
-    fn update(&mut self, _dt: f32, _data: &mut GameData) -> SceneSwitch {
+    fn update(&mut self, _dt: f32, data: &mut GameData) -> SceneSwitch {
+        data.audio.play_music(MENU_MUSIC);
         SceneSwitch::None
     }
 
@@ -33,8 +60,8 @@ impl Scene for MenuScene {
         // fallback if bg fails
         d.clear_background(Color::BLACK);
 
-        let screen_w = d.get_screen_width() as f32;
-        let screen_h = d.get_screen_height() as f32;
+        let screen_w = VIRTUAL_WIDTH as f32;
+        let screen_h = VIRTUAL_HEIGHT as f32;
 
         // render that main menu background
         if let Some(bg) = data.background_tex_vec.first() {
@@ -100,6 +127,37 @@ impl Scene for MenuScene {
             prompt_size,
             Color::LIGHTGRAY,
         );
+
+        let editor_prompt = "E - LEVEL EDITOR";
+        let editor_size = 20;
+        let editor_width = d.measure_text(editor_prompt, editor_size);
+        d.draw_text(
+            editor_prompt,
+            (screen_w / 2.0 - editor_width as f32 / 2.0) as i32,
+            (screen_h / 2.0 + 130.0) as i32,
+            editor_size,
+            Color::GRAY,
+        );
+
+        let settings_prompt = "S - SETTINGS";
+        let settings_width = d.measure_text(settings_prompt, editor_size);
+        d.draw_text(
+            settings_prompt,
+            (screen_w / 2.0 - settings_width as f32 / 2.0) as i32,
+            (screen_h / 2.0 + 160.0) as i32,
+            editor_size,
+            Color::GRAY,
+        );
+
+        let seed_prompt = format!("TOWER SEED {} - R FOR A NEW ONE", data.tower_seed);
+        let seed_width = d.measure_text(&seed_prompt, editor_size);
+        d.draw_text(
+            &seed_prompt,
+            (screen_w / 2.0 - seed_width as f32 / 2.0) as i32,
+            (screen_h / 2.0 + 190.0) as i32,
+            editor_size,
+            Color::GRAY,
+        );
     }
 
     fn on_exit(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) {}
@@ -0,0 +1,97 @@
+//! Layered backgrounds that follow the climb.
+//!
+//! Each stage stacks a few slices of its background art on top of a sky gradient. Layers
+//! scroll at their own fraction of the camera's climb, so far-off walls crawl while the
+//! courtyard below drops away quickly, and the sky shifts through colour bands on the way up.
+use raylib::prelude::*;
+
+use crate::canvas::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+
+/// One slice of background art, stretched to the screen width.
+#[derive(Debug, Clone, Copy)]
+pub struct ParallaxLayer {
+    /// Index into `GameData::background_tex_vec`.
+    pub texture: usize,
+    /// Part of the texture to draw, in pixels.
+    pub source: Rectangle,
+    /// Fraction of the camera's climb the layer follows. 0 sticks to the screen, 1 moves with the world.
+    pub rate: f32,
+    /// Tile vertically forever. Otherwise the layer sits at the bottom of the screen at the
+    /// start of the climb and scrolls away.
+    pub repeat: bool,
+    /// Climb progress (0 to 1) by which the layer has faded out completely. Anything past 1 never fades.
+    pub fade_out: f32,
+    pub tint: Color,
+}
+
+/// Sky colours once the climb reaches `at` (0 is the bottom, 1 the rooftop).
+#[derive(Debug, Clone, Copy)]
+pub struct SkyBand {
+    pub at: f32,
+    pub top: Color,
+    pub bottom: Color,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parallax {
+    /// Drawn back to front.
+    pub layers: Vec<ParallaxLayer>,
+    /// Sorted by `at`.
+    pub sky: Vec<SkyBand>,
+}
+
+impl Parallax {
+    /// Texture that best shows off the stage, for menus.
+    pub fn preview(&self) -> Option<usize> {
+        self.layers.last().map(|l| l.texture)
+    }
+
+    /// Sky gradient for the given progress, blended between the bands either side of it.
+    pub fn sky_colors(&self, progress: f32) -> (Color, Color) {
+        let Some(first) = self.sky.first() else { return (Color::SKYBLUE, Color::WHITE) };
+        let mut current = *first;
+        for band in &self.sky {
+            if progress <= band.at {
+                let span = band.at - current.at;
+                let t = if span > 0.0 { ((progress - current.at) / span).clamp(0.0, 1.0) } else { 1.0 };
+                return (current.top.lerp(band.top, t), current.bottom.lerp(band.bottom, t));
+            }
+            current = *band;
+        }
+        (current.top, current.bottom)
+    }
+
+    /// `climb` is how far the camera has risen in world units, `progress` the same as a 0-1 fraction of the tower.
+    pub fn draw(&self, d: &mut RaylibDrawHandle, textures: &[Texture2D], climb: f32, progress: f32, zoom: f32) {
+        let screen_w = VIRTUAL_WIDTH;
+        let screen_h = VIRTUAL_HEIGHT;
+
+        let (top, bottom) = self.sky_colors(progress);
+        d.draw_rectangle_gradient_v(0, 0, screen_w, screen_h, top, bottom);
+
+        for layer in &self.layers {
+            let Some(tex) = textures.get(layer.texture) else { continue };
+            let alpha = if layer.fade_out > 1.0 { 1.0 } else { 1.0 - (progress / layer.fade_out).clamp(0.0, 1.0) };
+            if alpha <= 0.0 { continue; }
+            let tint = layer.tint.alpha(alpha * layer.tint.a as f32 / 255.0);
+
+            let scale = screen_w as f32 / layer.source.width;
+            let h = layer.source.height * scale;
+            let shift = climb * layer.rate * zoom;
+
+            if layer.repeat {
+                // enough copies to cover the screen, wrapping as the camera rises
+                let mut y = shift.rem_euclid(h) - h;
+                while y < screen_h as f32 {
+                    d.draw_texture_pro(tex, layer.source, Rectangle::new(0.0, y, screen_w as f32, h), Vector2::zero(), 0.0, tint);
+                    y += h;
+                }
+            } else {
+                let y = screen_h as f32 - h + shift;
+                if y < screen_h as f32 {
+                    d.draw_texture_pro(tex, layer.source, Rectangle::new(0.0, y, screen_w as f32, h), Vector2::zero(), 0.0, tint);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Pooled particles for spell trails, impacts, lava and deaths.
+//!
+//! Every effect is an [`EmitterDef`]: plain data saying how many particles to throw, how long
+//! they live, which way they fly, how they fall and how their size and colour change over
+//! their life. The pool never grows past [`PARTICLE_BUDGET`]; once it's full, new particles
+//! are simply dropped so a busy fight can't drag the frame rate down.
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use raylib::prelude::*;
+
+/// Most particles alive at once.
+pub const PARTICLE_BUDGET: usize = 1500;
+
+// continuous emitters ease off once the pool is this full so bursts still have room
+const BUSY_FRACTION: f32 = 0.75;
+
+/// Straight line from `start` at birth to `end` at death.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Curve {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Curve {
+    pub const fn new(start: f32, end: f32) -> Self {
+        Self { start, end }
+    }
+
+    pub fn at(&self, t: f32) -> f32 {
+        self.start + (self.end - self.start) * t
+    }
+}
+
+/// One kind of effect.
+#[derive(Debug, Clone, Copy)]
+pub struct EmitterDef {
+    /// Particles per second for continuous effects like trails.
+    pub rate: f32,
+    /// Particles per one-off burst.
+    pub burst: usize,
+    /// Seconds, picked at random between the two.
+    pub lifetime: (f32, f32),
+    pub speed: (f32, f32),
+    /// Half-angle of the cone particles fly out in, in radians. PI throws them every way.
+    pub spread: f32,
+    /// Particles are born anywhere within this many units of the emitter, per axis.
+    pub area: (f32, f32),
+    pub gravity: f32,
+    /// Fraction of velocity lost per second.
+    pub drag: f32,
+    /// Radius over the particle's life.
+    pub size: Curve,
+    pub color_start: Color,
+    pub color_end: Color,
+}
+
+/// Fizzling sparks behind a flying bolt. Tinted per caster.
+pub const SPELL_TRAIL: EmitterDef = EmitterDef {
+    rate: 90.0,
+    burst: 0,
+    lifetime: (0.2, 0.4),
+    speed: (20.0, 80.0),
+    spread: 0.6,
+    area: (4.0, 4.0),
+    gravity: 0.0,
+    drag: 3.0,
+    size: Curve::new(6.0, 1.0),
+    color_start: Color::new(255, 255, 255, 220),
+    color_end: Color::new(255, 255, 255, 0),
+};
+
+/// A bolt fizzling out on a wall, or landing on a wizard.
+pub const IMPACT: EmitterDef = EmitterDef {
+    rate: 0.0,
+    burst: 14,
+    lifetime: (0.2, 0.45),
+    speed: (150.0, 420.0),
+    spread: 0.9,
+    area: (2.0, 2.0),
+    gravity: 900.0,
+    drag: 2.0,
+    size: Curve::new(5.0, 1.0),
+    color_start: Color::new(255, 240, 180, 255),
+    color_end: Color::new(255, 120, 40, 0),
+};
+
+/// Glowing bits drifting up off the lava.
+pub const LAVA_EMBER: EmitterDef = EmitterDef {
+    rate: 40.0,
+    burst: 0,
+    lifetime: (0.8, 1.8),
+    speed: (60.0, 180.0),
+    spread: 0.35,
+    area: (1100.0, 10.0),
+    gravity: -40.0,
+    drag: 0.5,
+    size: Curve::new(4.0, 0.5),
+    color_start: Color::new(255, 200, 80, 255),
+    color_end: Color::new(200, 40, 0, 0),
+};
+
+/// Slow bubbles swelling on the lava surface.
+pub const LAVA_BUBBLE: EmitterDef = EmitterDef {
+    rate: 12.0,
+    burst: 0,
+    lifetime: (0.5, 1.0),
+    speed: (5.0, 25.0),
+    spread: 0.2,
+    area: (1100.0, 4.0),
+    gravity: 0.0,
+    drag: 0.0,
+    size: Curve::new(3.0, 14.0),
+    color_start: Color::new(255, 120, 30, 200),
+    color_end: Color::new(255, 220, 120, 0),
+};
+
+/// Puff kicked up by jumping off or landing on something.
+pub const JUMP_DUST: EmitterDef = EmitterDef {
+    rate: 0.0,
+    burst: 10,
+    lifetime: (0.25, 0.5),
+    speed: (60.0, 200.0),
+    spread: 1.2,
+    area: (18.0, 2.0),
+    gravity: 200.0,
+    drag: 4.0,
+    size: Curve::new(7.0, 2.0),
+    color_start: Color::new(210, 200, 190, 180),
+    color_end: Color::new(160, 150, 140, 0),
+};
+
+/// A wizard going down in a blaze.
+pub const DEATH: EmitterDef = EmitterDef {
+    rate: 0.0,
+    burst: 70,
+    lifetime: (0.5, 1.1),
+    speed: (200.0, 700.0),
+    spread: PI,
+    area: (20.0, 30.0),
+    gravity: 600.0,
+    drag: 1.5,
+    size: Curve::new(9.0, 1.0),
+    color_start: Color::new(255, 255, 255, 255),
+    color_end: Color::new(120, 60, 200, 0),
+};
+
+/// Debris from a clash or a sticky bolt going off.
+pub const BLAST: EmitterDef = EmitterDef {
+    rate: 0.0,
+    burst: 30,
+    lifetime: (0.3, 0.6),
+    speed: (250.0, 600.0),
+    spread: PI,
+    area: (4.0, 4.0),
+    gravity: 500.0,
+    drag: 2.5,
+    size: Curve::new(7.0, 1.0),
+    color_start: Color::new(255, 230, 120, 255),
+    color_end: Color::new(220, 60, 20, 0),
+};
+
+/// Pointing straight up, for emitter directions.
+pub const UP: f32 = -FRAC_PI_2;
+
+struct Particle {
+    pos: Vector2,
+    vel: Vector2,
+    age: f32,
+    life: f32,
+    gravity: f32,
+    drag: f32,
+    size: Curve,
+    color_start: Color,
+    color_end: Color,
+}
+
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    rng: StdRng,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self { particles: Vec::with_capacity(PARTICLE_BUDGET), rng: StdRng::seed_from_u64(rand::random()) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Throws `def.burst` particles from `pos`, aimed along `angle` (radians).
+    pub fn burst(&mut self, def: &EmitterDef, pos: Vector2, angle: f32) {
+        self.spawn(def, pos, angle, def.burst);
+    }
+
+    /// Keeps a continuous effect going for one frame - on average `def.rate * dt` particles.
+    pub fn emit(&mut self, def: &EmitterDef, pos: Vector2, angle: f32, dt: f32) {
+        let mut expected = def.rate * dt;
+        if self.particles.len() as f32 > PARTICLE_BUDGET as f32 * BUSY_FRACTION { expected *= 0.25; }
+        let mut count = expected.floor() as usize;
+        if self.rng.random_bool((expected.fract() as f64).clamp(0.0, 1.0)) { count += 1; }
+        self.spawn(def, pos, angle, count);
+    }
+
+    fn spawn(&mut self, def: &EmitterDef, pos: Vector2, angle: f32, count: usize) {
+        let count = count.min(PARTICLE_BUDGET - self.particles.len());
+        for _ in 0..count {
+            let heading = angle + self.rng.random_range(-def.spread..=def.spread);
+            let speed = self.rng.random_range(def.speed.0..=def.speed.1);
+            let offset = Vector2::new(
+                self.rng.random_range(-def.area.0..=def.area.0),
+                self.rng.random_range(-def.area.1..=def.area.1),
+            );
+            self.particles.push(Particle {
+                pos: pos + offset,
+                vel: Vector2::new(heading.cos(), heading.sin()) * speed,
+                age: 0.0,
+                life: self.rng.random_range(def.lifetime.0..=def.lifetime.1).max(0.01),
+                gravity: def.gravity,
+                drag: def.drag,
+                size: def.size,
+                color_start: def.color_start,
+                color_end: def.color_end,
+            });
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for p in &mut self.particles {
+            p.age += dt;
+            p.vel.y += p.gravity * dt;
+            p.vel -= p.vel * (p.drag * dt).min(1.0);
+            p.pos += p.vel * dt;
+        }
+        // order doesn't matter, so dead ones are swapped out without shuffling the rest
+        let mut i = 0;
+        while i < self.particles.len() {
+            if self.particles[i].age >= self.particles[i].life { self.particles.swap_remove(i); } else { i += 1; }
+        }
+    }
+
+    /// Draws every live particle. Call inside 2D mode.
+    pub fn draw(&self, d: &mut RaylibMode2D<RaylibDrawHandle>) {
+        for p in &self.particles {
+            let t = p.age / p.life;
+            let radius = p.size.at(t);
+            if radius <= 0.0 { continue; }
+            d.draw_circle_v(p.pos, radius, p.color_start.lerp(p.color_end, t));
+        }
+    }
+}
+
+/// Copy of `def` recoloured, for effects like trails that take their caster's colour.
+pub fn tinted(def: &EmitterDef, color: Color) -> EmitterDef {
+    EmitterDef {
+        color_start: color.alpha(def.color_start.a as f32 / 255.0),
+        color_end: color.alpha(def.color_end.a as f32 / 255.0),
+        ..*def
+    }
+}
@@ -0,0 +1,81 @@
+use raylib::prelude::*;
+
+use crate::canvas::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+use crate::game_data::GameData;
+use crate::game_scene::GameScene;
+use crate::scenes::{Scene, SceneSwitch};
+use crate::settings_scene::SettingsScene;
+
+const OPTIONS: [&str; 5] = ["RESUME", "RESTART", "CONTROLS", "SETTINGS", "QUIT TO MENU"];
+const RESUME: usize = 0;
+const RESTART: usize = 1;
+const CONTROLS: usize = 2;
+const SETTINGS: usize = 3;
+const QUIT: usize = 4;
+
+// translucent menu over a frozen match - the game underneath doesn't update while this is on top
+pub struct PauseScene {
+    cursor: usize,
+    // a fresh copy of the paused match for the restart option
+    restart: Option<GameScene>,
+}
+
+impl PauseScene {
+    pub fn new(restart: GameScene) -> Self {
+        Self { cursor: RESUME, restart: Some(restart) }
+    }
+}
+
+impl Scene for PauseScene {
+    fn handle_input(&mut self, rl: &mut RaylibHandle, data: &mut GameData) -> SceneSwitch {
+        let pad = |rl: &RaylibHandle, button| rl.is_gamepad_button_pressed(0, button) || rl.is_gamepad_button_pressed(1, button);
+        let up = rl.is_key_pressed(KeyboardKey::KEY_UP) || rl.is_key_pressed(KeyboardKey::KEY_W) || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP);
+        let down = rl.is_key_pressed(KeyboardKey::KEY_DOWN) || rl.is_key_pressed(KeyboardKey::KEY_S) || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN);
+        let confirm = rl.is_key_pressed(KeyboardKey::KEY_ENTER) || pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+        let back = rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) || rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE)
+            || pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT) || pad(rl, GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT);
+
+        // backing out of the pause menu is the same as resuming
+        if back { return SceneSwitch::Pop; }
+        if up { self.cursor = (self.cursor + OPTIONS.len() - 1) % OPTIONS.len(); }
+        if down { self.cursor = (self.cursor + 1) % OPTIONS.len(); }
+        if confirm {
+            match self.cursor {
+                RESUME => return SceneSwitch::Pop,
+                RESTART => {
+                    if let Some(game) = self.restart.take() {
+                        data.player_scores = vec![0, 0];
+                        return SceneSwitch::PopThen(Box::new(SceneSwitch::Replace(Box::new(game))));
+                    }
+                }
+                CONTROLS => return SceneSwitch::Push(Box::new(SettingsScene::controls())),
+                SETTINGS => return SceneSwitch::Push(Box::new(SettingsScene::new())),
+                QUIT => return SceneSwitch::PopThen(Box::new(SceneSwitch::Pop)),
+                _ => (),
+            }
+        }
+        SceneSwitch::None
+    }
+
+    fn draw(&self, d: &mut RaylibDrawHandle, _data: &mut GameData) {
+        let screen_w = VIRTUAL_WIDTH;
+        let screen_h = VIRTUAL_HEIGHT;
+        d.draw_rectangle(0, 0, screen_w, screen_h, Color::BLACK.alpha(0.6));
+
+        let title = "PAUSED";
+        let title_w = d.measure_text(title, 90);
+        d.draw_text(title, screen_w / 2 - title_w / 2, screen_h / 5, 90, Color::WHITE);
+
+        let top = screen_h / 5 + 160;
+        for (i, option) in OPTIONS.iter().enumerate() {
+            let selected = i == self.cursor;
+            let label = if selected { format!("> {} <", option) } else { option.to_string() };
+            let w = d.measure_text(&label, 50);
+            d.draw_text(&label, screen_w / 2 - w / 2, top + i as i32 * 80, 50, if selected { Color::GOLD } else { Color::LIGHTGRAY });
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
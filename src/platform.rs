@@ -0,0 +1,213 @@
+use raylib::prelude::*;
+
+// crumbling platforms hang on for this long once stepped on, then come back later
+pub const CRUMBLE_DELAY: f32 = 0.6;
+pub const CRUMBLE_RESPAWN: f32 = 4.0;
+// mushrooms launch harder than a normal jump
+pub const BOUNCE_VELOCITY: f32 = -1750.0;
+// how quickly horizontal speed catches up to the stick on ice (per second)
+pub const ICE_TRACTION: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlatformKind {
+    Static,
+    // swings back and forth along `axis` by up to `range` units
+    Moving { axis: Vector2, range: f32, speed: f32 },
+    Crumbling,
+    Bouncy,
+    Ice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrumbleState {
+    Intact,
+    Crumbling(f32),
+    Gone(f32),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Platform {
+    pub rect: Rectangle,
+    pub kind: PlatformKind,
+    pub crumble: CrumbleState,
+    // authored levels can pick a tile, otherwise the kind decides
+    pub texture: Option<usize>,
+
+    // moving platform tracking - riders get carried by `delta` each frame
+    origin: Vector2,
+    phase: f32,
+    pub delta: Vector2,
+}
+
+impl Platform {
+    pub fn new(rect: Rectangle, kind: PlatformKind) -> Self {
+        Self {
+            rect,
+            kind,
+            crumble: CrumbleState::Intact,
+            texture: None,
+            origin: Vector2::new(rect.x, rect.y),
+            phase: 0.0,
+            delta: Vector2::zero(),
+        }
+    }
+
+    // moves the platform somewhere else (used when a reshuffling tower scatters it)
+    pub fn relocate(&mut self, x: f32, y: f32, width: f32) {
+        self.rect = Rectangle::new(x, y, width, self.rect.height);
+        self.origin = Vector2::new(x, y);
+        self.phase = 0.0;
+        self.crumble = CrumbleState::Intact;
+    }
+
+    // lifts or drops the platform by `dy`, mid-swing if it's a mover (used when a repeating
+    // tower brings its section back round)
+    pub fn shift_y(&mut self, dy: f32) {
+        self.rect.y += dy;
+        self.origin.y += dy;
+    }
+
+    // collapsed crumbling platforms can't be stood on or shot
+    pub fn is_solid(&self) -> bool {
+        !matches!(self.crumble, CrumbleState::Gone(_))
+    }
+
+    // starts the collapse the first time someone steps on a crumbling platform
+    pub fn on_stood_on(&mut self) {
+        if self.kind == PlatformKind::Crumbling && self.crumble == CrumbleState::Intact {
+            self.crumble = CrumbleState::Crumbling(CRUMBLE_DELAY);
+        }
+    }
+
+    // advances movement and the crumble/respawn cycle
+    pub fn update(&mut self, dt: f32) {
+        let before = Vector2::new(self.rect.x, self.rect.y);
+        if let PlatformKind::Moving { axis, range, speed } = self.kind {
+            self.phase += speed * dt;
+            let offset = axis * (range * self.phase.sin());
+            self.rect.x = self.origin.x + offset.x;
+            self.rect.y = self.origin.y + offset.y;
+        }
+        self.delta = Vector2::new(self.rect.x - before.x, self.rect.y - before.y);
+
+        self.crumble = match self.crumble {
+            CrumbleState::Crumbling(t) if t - dt <= 0.0 => CrumbleState::Gone(CRUMBLE_RESPAWN),
+            CrumbleState::Crumbling(t) => CrumbleState::Crumbling(t - dt),
+            CrumbleState::Gone(t) if t - dt <= 0.0 => CrumbleState::Intact,
+            CrumbleState::Gone(t) => CrumbleState::Gone(t - dt),
+            CrumbleState::Intact => CrumbleState::Intact,
+        };
+    }
+
+    // each behaviour gets its own tile from the stage's `tileset` and a tint so players can read
+    // the tower at a glance. `index` spreads the plain platforms across the tiles, and an
+    // authored texture picks straight from `textures`
+    pub fn draw(&self, d: &mut RaylibMode2D<RaylibDrawHandle>, textures: &[Texture2D], tileset: &[usize], index: usize, time: f64) {
+        let (tex_index, mut tint) = match self.kind {
+            PlatformKind::Static => (index % 3, Color::WHITE),
+            PlatformKind::Moving { .. } => (1, Color::new(200, 220, 255, 255)),
+            PlatformKind::Crumbling => (2, Color::new(190, 140, 100, 255)),
+            PlatformKind::Bouncy => (0, Color::new(255, 120, 200, 255)),
+            PlatformKind::Ice => (1, Color::new(150, 230, 255, 255)),
+        };
+        let tex_index = self.texture.unwrap_or(tileset[tex_index % tileset.len()]);
+        let tex = &textures[tex_index % textures.len()];
+        let mut dest = self.rect;
+        match self.crumble {
+            CrumbleState::Crumbling(t) => {
+                // shake harder as it's about to go
+                dest.x += ((time * 60.0).sin() as f32) * 3.0 * (1.0 - t / CRUMBLE_DELAY);
+            }
+            CrumbleState::Gone(_) => tint = tint.alpha(0.15),
+            CrumbleState::Intact => (),
+        }
+        d.draw_texture_pro(tex, Rectangle::new(0.0,16.0,48.0,15.0), dest, Vector2::zero(), 0.0, tint);
+
+        match self.kind {
+            // springy cap on top of mushrooms
+            PlatformKind::Bouncy => {
+                let cap = Rectangle::new(dest.x + 6.0, dest.y - 8.0, dest.width - 12.0, 10.0);
+                d.draw_rectangle_rounded(cap, 1.0, 8, Color::new(230, 60, 120, 255));
+            }
+            // shiny streak across ice
+            PlatformKind::Ice => {
+                d.draw_line_ex(Vector2::new(dest.x + 4.0, dest.y + 3.0), Vector2::new(dest.x + dest.width - 4.0, dest.y + 3.0), 3.0, Color::WHITE.alpha(0.8));
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mover_delta_matches_its_displacement() {
+        let kind = PlatformKind::Moving { axis: Vector2::new(1.0, 0.0), range: 150.0, speed: 1.5 };
+        let mut plat = Platform::new(Rectangle::new(100.0, 200.0, 120.0, 20.0), kind);
+        for _ in 0..120 {
+            let before = Vector2::new(plat.rect.x, plat.rect.y);
+            plat.update(1.0 / 60.0);
+            assert!((plat.delta.x - (plat.rect.x - before.x)).abs() < 1e-4);
+            assert!((plat.delta.y - (plat.rect.y - before.y)).abs() < 1e-4);
+        }
+        // it actually went somewhere, and only along its axis
+        assert!(plat.rect.x != 100.0);
+        assert_eq!(plat.rect.y, 200.0);
+    }
+
+    #[test]
+    fn mover_keeps_its_path_when_shifted() {
+        let kind = PlatformKind::Moving { axis: Vector2::new(1.0, 0.0), range: 150.0, speed: 1.5 };
+        let mut plat = Platform::new(Rectangle::new(100.0, 200.0, 120.0, 20.0), kind);
+        let mut twin = plat;
+        for recycle in 1..=4 {
+            for _ in 0..37 {
+                plat.update(1.0 / 60.0);
+                twin.update(1.0 / 60.0);
+            }
+            // recycled while it's off to one side
+            assert!(plat.rect.x != 100.0);
+            plat.shift_y(-1000.0);
+            assert_eq!(plat.rect.x, twin.rect.x);
+            assert_eq!(plat.rect.y, 200.0 - 1000.0 * recycle as f32);
+        }
+        // still swinging around where it was authored
+        for _ in 0..120 {
+            plat.update(1.0 / 60.0);
+            twin.update(1.0 / 60.0);
+            assert!((plat.rect.x - twin.rect.x).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn crumbling_platform_falls_apart_and_comes_back() {
+        let mut plat = Platform::new(Rectangle::new(0.0, 0.0, 120.0, 20.0), PlatformKind::Crumbling);
+        let step = 0.1;
+
+        plat.on_stood_on();
+        assert_eq!(plat.crumble, CrumbleState::Crumbling(CRUMBLE_DELAY));
+        assert!(plat.is_solid());
+
+        // hangs on for the delay...
+        let mut t = 0.0;
+        while plat.crumble != CrumbleState::Gone(CRUMBLE_RESPAWN) {
+            plat.update(step);
+            t += step;
+            assert!(t <= CRUMBLE_DELAY + step, "never collapsed");
+        }
+        assert!(t >= CRUMBLE_DELAY - step);
+        assert!(!plat.is_solid());
+
+        // ...then stays gone until it respawns
+        let mut t = 0.0;
+        while plat.crumble != CrumbleState::Intact {
+            plat.update(step);
+            t += step;
+            assert!(t <= CRUMBLE_RESPAWN + step, "never came back");
+        }
+        assert!(t >= CRUMBLE_RESPAWN - step);
+        assert!(plat.is_solid());
+    }
+}
@@ -1,5 +1,73 @@
 use raylib::prelude::*;
 
+// basic movement tuning
+pub const GRAVITY: f32 = 2400.0;
+pub const MOVE_SPEED: f32 = 350.0;
+pub const JUMP_VELOCITY: f32 = -1200.0;
+pub const DOUBLE_JUMP_VELOCITY: f32 = -1000.0;
+// hitbox size
+pub const BODY_WIDTH: f32 = 50.0;
+pub const BODY_HEIGHT: f32 = 80.0;
+// forgiveness windows - jumping just after walking off a ledge, and pressing jump just before landing
+pub const COYOTE_TIME: f32 = 0.1;
+pub const JUMP_BUFFER_TIME: f32 = 0.12;
+// letting go of jump early keeps this much of the upward speed
+pub const JUMP_RELEASE_CUT: f32 = 0.45;
+// dropping through a platform - ignore it for this long, or hold down this long to drop
+pub const DROP_THROUGH_TIME: f32 = 0.25;
+pub const DROP_HOLD_TIME: f32 = 0.3;
+// wall slide caps the fall speed, wall jump kicks away from the wall
+pub const WALL_SLIDE_SPEED: f32 = 150.0;
+pub const WALL_JUMP_VELOCITY: f32 = -1100.0;
+pub const WALL_JUMP_PUSH: f32 = 600.0;
+// stops the stick from instantly cancelling the wall jump push
+pub const WALL_JUMP_LOCK: f32 = 0.15;
+// air dash - short burst that ignores gravity and can't be hit
+pub const DASH_SPEED: f32 = 1100.0;
+pub const DASH_DURATION: f32 = 0.15;
+pub const DASH_COOLDOWN: f32 = 0.6;
+// three hits and you're out
+pub const MAX_HP: i32 = 3;
+// respawning wizards float down onto their platform, then can't be hurt for a moment
+// unless they start casting first
+pub const SPAWN_DESCENT_SPEED: f32 = 500.0;
+pub const SPAWN_PROTECTION_TIME: f32 = 2.0;
+
+// how long fire has to be held to reach each charge level
+pub const CHARGE_TIME_PER_LEVEL: f32 = 0.45;
+pub const MAX_CHARGE_LEVEL: u32 = 3;
+// movement multiplier while winding up a spell
+pub const CHARGE_MOVE_SCALE: f32 = 0.45;
+// first few attack frames get looped while the spell is charging
+pub const CHARGE_LOOP_FRAMES: usize = 2;
+
+// parry timing - reflect window after pressing, then a lockout before the next try
+pub const PARRY_WINDOW: f32 = 0.18;
+pub const PARRY_COOLDOWN: f32 = 0.7;
+// how far outside the hitbox a parry can still catch a bolt
+pub const PARRY_REACH: f32 = 30.0;
+
+// shield durability - drains per point of incoming spell power, refills after a short delay
+pub const SHIELD_MAX: f32 = 100.0;
+pub const SHIELD_DRAIN_PER_POWER: f32 = 30.0;
+pub const SHIELD_REGEN_RATE: f32 = 30.0;
+pub const SHIELD_REGEN_DELAY: f32 = 1.0;
+// breaking the shield leaves the wizard dazed for this long
+pub const SHIELD_BREAK_STUN: f32 = 1.2;
+pub const SHIELD_MOVE_SCALE: f32 = 0.3;
+// the barrier covers directions within ~70 degrees of the aim
+pub const SHIELD_ARC_COS: f32 = 0.35;
+pub const SHIELD_RADIUS: f32 = 70.0;
+
+// getting hit shoves the victim along the bolt's path, harder for charged spells
+pub const KNOCKBACK_BASE: f32 = 450.0;
+pub const KNOCKBACK_PER_POWER: f32 = 200.0;
+pub const KNOCKBACK_LIFT: f32 = 350.0;
+// horizontal slowdown while flying from a hit, since input isn't driving vel.x
+pub const KNOCKBACK_DRAG: f32 = 3.0;
+pub const HITSTUN_BASE: f32 = 0.2;
+pub const HITSTUN_PER_POWER: f32 = 0.08;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AnimationState {
     Idle,
@@ -7,8 +75,38 @@ pub enum AnimationState {
     Jump,
     Shoot,
     Hurt,
+    Shield,
+    Stunned,
+    Dash,
+    WallSlide,
+}
+
+// which movement tricks a character gets to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovementAbilities {
+    pub double_jump: bool,
+    pub air_dash: bool,
+    pub wall_jump: bool,
+}
+
+impl MovementAbilities {
+    pub const FULL: MovementAbilities = MovementAbilities { double_jump: true, air_dash: true, wall_jump: true };
+
+    // what each wizard gets, by player slot - both have the whole kit for now,
+    // switch a flag off here to give one of them a different feel
+    pub fn for_character(id: i32) -> Self {
+        CHARACTER_ABILITIES.get(id as usize).copied().unwrap_or_default()
+    }
+}
+
+impl Default for MovementAbilities {
+    fn default() -> Self {
+        Self::FULL
+    }
 }
 
+pub const CHARACTER_ABILITIES: [MovementAbilities; 2] = [MovementAbilities::FULL, MovementAbilities::FULL];
+
 #[derive(Clone)]
 pub struct Player {
     pub pos: Vector2,
@@ -20,6 +118,29 @@ pub struct Player {
     pub shooting: bool,
     pub facing_left: bool,
 
+    // movement kit
+    pub abilities: MovementAbilities,
+    pub move_input: f32,
+    pub air_jumps_left: u32,
+    pub air_dash_ready: bool,
+    pub dash_timer: f32,
+    pub dash_cooldown: f32,
+    pub wall_contact: i32, // -1 touching a wall on the left, 1 on the right, 0 none
+    pub wall_sliding: bool,
+    pub wall_jump_lock: f32,
+    pub on_ice: bool,
+
+    // jump forgiveness and variable height
+    pub coyote_timer: f32,
+    pub jump_buffer_timer: f32,
+    pub jumping: bool,
+
+    // one-way platform drop-through
+    pub ground_platform: Option<usize>,
+    pub drop_platform: Option<usize>,
+    pub drop_timer: f32,
+    pub down_hold_time: f32,
+
     // animation state tracking
     pub anim_state: AnimationState,
     pub anim_frame: usize,
@@ -29,13 +150,32 @@ pub struct Player {
     pub attack_fired: bool,
     pub hit: bool,
 
+    // hold-to-charge spell state
+    pub charging: bool,
+    pub charge_time: f32,
+
+    // timed reflect state
+    pub parry_timer: f32,
+    pub parry_cooldown: f32,
+
+    // directional barrier and the daze after it breaks
+    pub shielding: bool,
+    pub shield_hp: f32,
+    pub shield_regen_timer: f32,
+    pub stun_timer: f32,
+    pub hitstun_timer: f32,
+
     // health and damage cooldowns
     pub hp: i32,
     pub i_frame_timer: f32,
+
+    // respawning - the platform being floated down to, then the protection after landing
+    pub spawn_platform: Option<usize>,
+    pub spawn_protection: f32,
 }
 
 impl Player {
-    pub fn new(id: i32, x: f32, y: f32) -> Self {
+    pub fn new(id: i32, x: f32, y: f32, abilities: MovementAbilities) -> Self {
         Self {
             pos: Vector2::new(x, y),
             vel: Vector2::zero(),
@@ -46,6 +186,26 @@ impl Player {
             shooting: false,
             facing_left: false,
 
+            abilities,
+            move_input: 0.0,
+            air_jumps_left: 1,
+            air_dash_ready: true,
+            dash_timer: 0.0,
+            dash_cooldown: 0.0,
+            wall_contact: 0,
+            wall_sliding: false,
+            wall_jump_lock: 0.0,
+            on_ice: false,
+
+            coyote_timer: 0.0,
+            jump_buffer_timer: 0.0,
+            jumping: false,
+
+            ground_platform: None,
+            drop_platform: None,
+            drop_timer: 0.0,
+            down_hold_time: 0.0,
+
             // starting animation values
             anim_state: AnimationState::Idle,
             anim_frame: 0,
@@ -55,21 +215,275 @@ impl Player {
             attack_fired: false,
             hit: false,
 
-            // three hits and you're out
-            hp: 3,
+            charging: false,
+            charge_time: 0.0,
+
+            parry_timer: 0.0,
+            parry_cooldown: 0.0,
+
+            shielding: false,
+            shield_hp: SHIELD_MAX,
+            shield_regen_timer: 0.0,
+            stun_timer: 0.0,
+            hitstun_timer: 0.0,
+
+            hp: MAX_HP,
             i_frame_timer: 0.0,
+
+            spawn_platform: None,
+            spawn_protection: 0.0,
         }
     }
 
     // helper to see if we're still in that post-hit blink
     pub fn is_invincible(&self) -> bool {
-        self.i_frame_timer > 0.0
+        self.i_frame_timer > 0.0 || self.is_spawning() || self.spawn_protection > 0.0
+    }
+
+    // moves the wizard somewhere new, forgetting whatever platform it was standing on,
+    // dropping through or floating down to
+    pub fn teleport(&mut self, pos: Vector2) {
+        self.pos = pos;
+        self.vel = Vector2::zero();
+        self.grounded = false;
+        self.ground_platform = None;
+        self.drop_platform = None;
+        self.spawn_platform = None;
+    }
+
+    // back in the fight with full hearts at `pos`, floating down to `platform` if there is one.
+    // without a platform to aim for the protection starts straight away
+    pub fn respawn(&mut self, pos: Vector2, platform: Option<usize>) {
+        self.pos = pos;
+        self.vel = Vector2::zero();
+        self.hp = MAX_HP;
+        self.hit = false;
+        self.i_frame_timer = 0.0;
+        self.hitstun_timer = 0.0;
+        self.stun_timer = 0.0;
+        self.dash_timer = 0.0;
+        self.shield_hp = SHIELD_MAX;
+        self.shielding = false;
+        self.move_input = 0.0;
+        self.grounded = false;
+        self.cancel_charge();
+        self.spawn_platform = platform;
+        self.spawn_protection = if platform.is_some() { 0.0 } else { SPAWN_PROTECTION_TIME };
+    }
+
+    // still floating down after a respawn - no input, no gravity
+    pub fn is_spawning(&self) -> bool {
+        self.spawn_platform.is_some()
+    }
+
+    // touched down on the spawn platform (or lost it), the protection starts ticking
+    pub fn finish_spawn(&mut self, platform: Option<usize>) {
+        self.spawn_platform = None;
+        self.spawn_protection = SPAWN_PROTECTION_TIME;
+        if platform.is_some() {
+            self.grounded = true;
+            self.ground_platform = platform;
+            self.on_landed();
+        }
+    }
+
+    // out of hearts - lying there until the round resets or the respawn comes
+    pub fn is_down(&self) -> bool {
+        self.hp <= 0
+    }
+
+    // input is locked out while this is running
+    pub fn is_stunned(&self) -> bool {
+        self.stun_timer > 0.0
+    }
+
+    // remembers the press for a moment so it still counts if we land just after
+    pub fn buffer_jump(&mut self) {
+        self.jump_buffer_timer = JUMP_BUFFER_TIME;
+    }
+
+    // spends a buffered jump press if there's any way to jump right now
+    // returns true if it turned into a jump this frame
+    pub fn try_buffered_jump(&mut self) -> bool {
+        if self.jump_buffer_timer > 0.0 && self.try_jump() {
+            self.jump_buffer_timer = 0.0;
+            return true;
+        }
+        false
+    }
+
+    // ground jump (coyote time counts), then wall jump, then the spare air jump -
+    // whichever applies first. returns false if none did
+    pub fn try_jump(&mut self) -> bool {
+        if self.grounded || self.coyote_timer > 0.0 {
+            self.vel.y = JUMP_VELOCITY;
+            self.grounded = false;
+            self.coyote_timer = 0.0;
+        } else if self.abilities.wall_jump && self.wall_contact != 0 {
+            self.vel.y = WALL_JUMP_VELOCITY;
+            self.vel.x = -(self.wall_contact as f32) * WALL_JUMP_PUSH;
+            self.facing_left = self.wall_contact > 0;
+            self.wall_jump_lock = WALL_JUMP_LOCK;
+            self.wall_sliding = false;
+        } else if self.abilities.double_jump && self.air_jumps_left > 0 {
+            self.air_jumps_left -= 1;
+            self.vel.y = DOUBLE_JUMP_VELOCITY;
+        } else {
+            return false;
+        }
+        self.jumping = true;
+        true
+    }
+
+    // short hop if jump was let go while still rising
+    pub fn release_jump(&mut self) {
+        if self.jumping && self.vel.y < 0.0 {
+            self.vel.y *= JUMP_RELEASE_CUT;
+        }
+        self.jumping = false;
+    }
+
+    // falls through the platform we're standing on, ignoring just that one for a moment
+    pub fn start_drop(&mut self, platform: usize) {
+        self.drop_platform = Some(platform);
+        self.drop_timer = DROP_THROUGH_TIME;
+        self.ground_platform = None;
+        self.grounded = false;
+        self.vel.y = self.vel.y.max(50.0);
+        self.coyote_timer = 0.0;
+        self.jump_buffer_timer = 0.0;
+        self.down_hold_time = 0.0;
+    }
+
+    // true while a specific platform should be passed through
+    pub fn is_dropping_through(&self, platform: usize) -> bool {
+        self.drop_timer > 0.0 && self.drop_platform == Some(platform)
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        self.dash_timer > 0.0
+    }
+
+    // one dash per trip through the air, along the held direction or the way we're facing
+    pub fn try_dash(&mut self) {
+        if !self.abilities.air_dash || self.grounded || !self.air_dash_ready || self.dash_cooldown > 0.0 {
+            return;
+        }
+        let dir = if self.move_input != 0.0 { self.move_input.signum() } else if self.facing_left { -1.0 } else { 1.0 };
+        self.vel = Vector2::new(dir * DASH_SPEED, 0.0);
+        self.facing_left = dir < 0.0;
+        self.dash_timer = DASH_DURATION;
+        self.dash_cooldown = DASH_COOLDOWN;
+        self.air_dash_ready = false;
+        // brief invulnerability for the length of the dash
+        self.i_frame_timer = self.i_frame_timer.max(DASH_DURATION + 0.05);
+    }
+
+    // holding into a wall while falling turns into a slide
+    pub fn update_wall_slide(&mut self) {
+        // signum of 0.0 is 1.0, so no input has to be ruled out first
+        let pushing_wall = self.wall_contact != 0 && self.move_input != 0.0 && self.move_input.signum() == self.wall_contact as f32;
+        self.wall_sliding = self.abilities.wall_jump && !self.grounded && pushing_wall && self.vel.y >= 0.0;
+    }
+
+    // touching down gives back the air jump and the dash
+    pub fn on_landed(&mut self) {
+        self.air_jumps_left = 1;
+        self.air_dash_ready = true;
+        self.wall_sliding = false;
+        self.jumping = false;
+        self.coyote_timer = COYOTE_TIME;
+    }
+
+    // reeling from a hit - input is ignored so the knockback carries them
+    pub fn in_hitstun(&self) -> bool {
+        self.hitstun_timer > 0.0
+    }
+
+    // launches the wizard away from a hit and starts the hitstun
+    pub fn apply_knockback(&mut self, direction: Vector2, power: u32) {
+        let strength = KNOCKBACK_BASE + KNOCKBACK_PER_POWER * power as f32;
+        let dir = if direction.length() > 0.0 { direction.normalized() } else { Vector2::zero() };
+        self.vel = dir * strength;
+        self.vel.y -= KNOCKBACK_LIFT;
+        self.grounded = false;
+        self.shielding = false;
+        self.dash_timer = 0.0;
+        self.jumping = false;
+        self.hitstun_timer = HITSTUN_BASE + HITSTUN_PER_POWER * power as f32;
+    }
+
+    // barrier is only up if there's durability left to soak hits
+    pub fn is_shielding(&self) -> bool {
+        self.shielding && self.shield_hp > 0.0 && !self.is_stunned()
+    }
+
+    // roughly the middle of the wizard's body, used for the shield and parry ring
+    pub fn body_center(&self) -> Vector2 {
+        Vector2::new(self.pos.x, self.pos.y - self.height * 0.35)
+    }
+
+    // does the raised shield face the given point
+    pub fn shield_covers(&self, point: Vector2) -> bool {
+        let to_point = point - self.body_center();
+        if to_point.length() == 0.0 {
+            return true;
+        }
+        to_point.normalized().dot(self.aim) >= SHIELD_ARC_COS
+    }
+
+    // soaks a spell on the shield, breaking it and stunning the wizard if it runs dry
+    pub fn absorb_hit(&mut self, power: u32) {
+        self.shield_hp -= SHIELD_DRAIN_PER_POWER * power as f32;
+        self.shield_regen_timer = SHIELD_REGEN_DELAY;
+        if self.shield_hp <= 0.0 {
+            self.shield_hp = 0.0;
+            self.shielding = false;
+            self.stun_timer = SHIELD_BREAK_STUN;
+            self.cancel_charge();
+        }
+    }
+
+    // ticks the stun and refills the shield once it's been left alone for a bit
+    pub fn update_shield(&mut self, dt: f32) {
+        if self.stun_timer > 0.0 { self.stun_timer -= dt; }
+        if self.is_shielding() { return; }
+        if self.shield_regen_timer > 0.0 {
+            self.shield_regen_timer -= dt;
+        } else {
+            self.shield_hp = (self.shield_hp + SHIELD_REGEN_RATE * dt).min(SHIELD_MAX);
+        }
+    }
+
+    // true during the short window where incoming bolts get sent back
+    pub fn is_parrying(&self) -> bool {
+        self.parry_timer > 0.0
+    }
+
+    // opens the reflect window if the last attempt has cooled down
+    pub fn try_parry(&mut self) {
+        if self.parry_cooldown <= 0.0 {
+            self.parry_timer = PARRY_WINDOW;
+            self.parry_cooldown = PARRY_COOLDOWN;
+        }
+    }
+
+    // 0 is a quick tap, MAX_CHARGE_LEVEL is a fully charged blast
+    pub fn charge_level(&self) -> u32 {
+        ((self.charge_time / CHARGE_TIME_PER_LEVEL) as u32).min(MAX_CHARGE_LEVEL)
+    }
+
+    // drops whatever spell was being wound up (e.g. when getting hit)
+    pub fn cancel_charge(&mut self) {
+        self.charging = false;
+        self.shooting = false;
+        self.charge_time = 0.0;
     }
 
     // custom hitbox dimensions
     pub fn rect(&self) -> Rectangle {
-        let w = 50.0;
-        let h = 80.0;
+        let w = BODY_WIDTH;
+        let h = BODY_HEIGHT;
         let x = self.pos.x - (w / 2.0) - 10.0;
         let y = self.pos.y - h;
         Rectangle::new(x, y, w, h)
@@ -80,10 +494,22 @@ impl Player {
         if self.hit {
             return AnimationState::Hurt;
         }
+        if self.is_stunned() {
+            return AnimationState::Stunned;
+        }
+        if self.is_dashing() {
+            return AnimationState::Dash;
+        }
+        if self.is_shielding() {
+            return AnimationState::Shield;
+        }
         if self.shooting {
             return AnimationState::Shoot;
         }
 
+        if self.wall_sliding {
+            return AnimationState::WallSlide;
+        }
         if !self.grounded {
             return AnimationState::Jump;
         }
@@ -103,4 +529,57 @@ impl Player {
             self.pos.y - self.height * 0.65,
         )
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a wizard in mid-air, falling, with the given kit
+    fn airborne(abilities: MovementAbilities) -> Player {
+        let mut player = Player::new(0, 500.0, 500.0, abilities);
+        player.grounded = false;
+        player.coyote_timer = 0.0;
+        player.vel.y = 100.0;
+        player
+    }
+
+    #[test]
+    fn no_double_jump_without_the_ability() {
+        let mut player = airborne(MovementAbilities { double_jump: false, ..MovementAbilities::FULL });
+        assert!(!player.try_jump());
+        assert_eq!(player.vel.y, 100.0);
+
+        let mut player = airborne(MovementAbilities::FULL);
+        assert!(player.try_jump());
+        assert_eq!(player.vel.y, DOUBLE_JUMP_VELOCITY);
+    }
+
+    #[test]
+    fn no_air_dash_without_the_ability() {
+        let mut player = airborne(MovementAbilities { air_dash: false, ..MovementAbilities::FULL });
+        player.try_dash();
+        assert!(!player.is_dashing());
+
+        let mut player = airborne(MovementAbilities::FULL);
+        player.try_dash();
+        assert!(player.is_dashing());
+    }
+
+    #[test]
+    fn no_wall_slide_without_input() {
+        let mut player = airborne(MovementAbilities::FULL);
+        player.wall_contact = 1;
+        player.move_input = 0.0;
+        player.update_wall_slide();
+        assert!(!player.wall_sliding);
+
+        // pushing away from the wall doesn't count either
+        player.move_input = -1.0;
+        player.update_wall_slide();
+        assert!(!player.wall_sliding);
+
+        player.move_input = 1.0;
+        player.update_wall_slide();
+        assert!(player.wall_sliding);
+    }
+}
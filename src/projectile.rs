@@ -1,11 +1,29 @@
 use raylib::prelude::*;
 
+use crate::utils::sweep_circle_rec;
+
+// what a bolt does when it runs into a platform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImpactBehavior {
+    Destroy,
+    Ricochet { bounces_left: u32 },
+    Stick { fuse: f32 },
+}
+
 pub struct Projectile {
     pub pos: Vector2,
     pub vel: Vector2,
     pub color: Color,
-    pub active: bool,  
-    pub owner_id: i32, 
+    pub active: bool,
+    pub owner_id: i32,
+    pub radius: f32,
+    pub damage: i32,
+    pub level: u32,
+
+    // platform impact handling
+    pub behavior: ImpactBehavior,
+    pub stuck: bool,
+    pub exploded: bool,
 }
 
 impl Projectile {
@@ -17,12 +35,106 @@ impl Projectile {
             color,
             active: true,
             owner_id,
+            radius: 10.0,
+            damage: 1,
+            level: 0,
+            behavior: ImpactBehavior::Destroy,
+            stuck: false,
+            exploded: false,
         }
     }
 
-    // moves the bolt and kills it if it flies off-screen
-    pub fn update(&mut self, dt: f32) {
-        self.pos += self.vel * dt;
+    // bigger, faster and meaner the longer the caster held the button
+    pub fn charged(pos: Vector2, aim: Vector2, owner_id: i32, color: Color, level: u32) -> Self {
+        let speed = 800.0 + 150.0 * level as f32;
+        let mut bolt = Self::new(pos, aim * speed, owner_id, color);
+        bolt.set_level(level);
+        bolt
+    }
+
+    // size, damage and what happens on impact all follow the charge level - a bolt knocked
+    // down a level in a clash turns into that level's spell
+    fn set_level(&mut self, level: u32) {
+        self.radius = 10.0 + 5.0 * level as f32;
+        self.damage = 1 + (level / 2) as i32;
+        self.level = level;
+
+        // each charge tier is its own spell: quick shots fizzle, mid charges bank off walls,
+        // and a full charge sticks where it lands and goes off a moment later
+        self.behavior = match level {
+            0 | 1 => ImpactBehavior::Destroy,
+            2 => ImpactBehavior::Ricochet { bounces_left: 2 },
+            _ => ImpactBehavior::Stick { fuse: 0.8 },
+        };
+    }
+
+    // tap shots are worth 1, a full charge is worth 4
+    pub fn power(&self) -> u32 {
+        self.level + 1
+    }
+
+    // sends the bolt straight back at whoever cast it, now owned by the reflector
+    pub fn reflect(&mut self, new_owner: i32) {
+        self.vel = -self.vel * 1.2;
+        self.owner_id = new_owner;
+    }
+
+    // how far a detonating sticky bolt reaches
+    pub fn blast_radius(&self) -> f32 {
+        self.radius * 6.0
+    }
+
+    // moves the bolt, resolves platform hits and kills it if it flies off-screen
+    pub fn update(&mut self, dt: f32, platforms: &[Rectangle]) {
+        if self.stuck {
+            if let ImpactBehavior::Stick { fuse } = &mut self.behavior {
+                *fuse -= dt;
+                if *fuse <= 0.0 {
+                    self.exploded = true;
+                    self.active = false;
+                }
+            }
+            return;
+        }
+
+        // a few passes so a ricochet keeps the rest of its movement this frame
+        let mut remaining = dt;
+        for _ in 0..3 {
+            let motion = self.vel * remaining;
+            let hit = platforms
+                .iter()
+                .filter_map(|plat| sweep_circle_rec(self.pos, motion, self.radius, *plat))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+
+            let Some((t, normal)) = hit else {
+                self.pos += motion;
+                break;
+            };
+
+            self.pos += motion * t;
+            match &mut self.behavior {
+                ImpactBehavior::Destroy => {
+                    self.active = false;
+                    break;
+                }
+                ImpactBehavior::Ricochet { bounces_left } => {
+                    if *bounces_left == 0 {
+                        self.active = false;
+                        break;
+                    }
+                    *bounces_left -= 1;
+                    self.vel -= normal * (2.0 * self.vel.dot(normal));
+                    // nudge off the surface so the next sweep doesn't start touching it
+                    self.pos += normal * 0.5;
+                    remaining *= 1.0 - t;
+                }
+                ImpactBehavior::Stick { .. } => {
+                    self.stuck = true;
+                    self.vel = Vector2::zero();
+                    break;
+                }
+            }
+        }
 
         // generous bounds so they don't despawn too early during the tower climb
         if self.pos.x < -500.0 || self.pos.x > 2500.0 || self.pos.y < -10000.0 || self.pos.y > 2000.0 {
@@ -32,6 +144,88 @@ impl Projectile {
 
     // fallback draw call if we aren't using the fancy glow in game_scene
     pub fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.draw_circle_v(self.pos, 10.0, self.color);
+        d.draw_circle_v(self.pos, self.radius, self.color);
+    }
+}
+
+// two opposing bolts met - equal power cancels out, otherwise the stronger one
+// pushes through with whatever power it has left
+pub fn resolve_clash(a: &mut Projectile, b: &mut Projectile) {
+    let (pa, pb) = (a.power(), b.power());
+    if pa == pb {
+        a.active = false;
+        b.active = false;
+    } else if pa > pb {
+        b.active = false;
+        a.set_level(pa - pb - 1);
+    } else {
+        a.active = false;
+        b.set_level(pb - pa - 1);
     }
-}
\ No newline at end of file
+}
+
+// short-lived ring left behind when a bolt goes off (sticky detonations, clashes)
+pub struct Explosion {
+    pub pos: Vector2,
+    pub radius: f32,
+    pub timer: f32,
+}
+
+impl Explosion {
+    pub const DURATION: f32 = 0.3;
+
+    pub fn new(pos: Vector2, radius: f32) -> Self {
+        Self { pos, radius, timer: Self::DURATION }
+    }
+
+    // 0.0 when it just went off, 1.0 when it's done
+    pub fn progress(&self) -> f32 {
+        1.0 - (self.timer / Self::DURATION).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bolt(owner_id: i32, level: u32) -> Projectile {
+        Projectile::charged(Vector2::zero(), Vector2::new(1.0, 0.0), owner_id, Color::WHITE, level)
+    }
+
+    #[test]
+    fn equal_power_cancels_out() {
+        let (mut a, mut b) = (bolt(0, 2), bolt(1, 2));
+        resolve_clash(&mut a, &mut b);
+        assert!(!a.active && !b.active);
+    }
+
+    #[test]
+    fn stronger_bolt_pushes_through_weakened() {
+        // a full charge (power 4) against a tap (power 1) comes out at level 2
+        let (mut a, mut b) = (bolt(0, 3), bolt(1, 0));
+        resolve_clash(&mut a, &mut b);
+        assert!(a.active && !b.active);
+        assert_eq!(a.level, 2);
+        assert_eq!(a.power(), 3);
+
+        // and the same the other way round
+        let (mut a, mut b) = (bolt(0, 1), bolt(1, 2));
+        resolve_clash(&mut a, &mut b);
+        assert!(!a.active && b.active);
+        assert_eq!(b.level, 0);
+    }
+
+    #[test]
+    fn demoted_bolt_takes_its_new_levels_spell() {
+        let (mut a, mut b) = (bolt(0, 3), bolt(1, 0));
+        resolve_clash(&mut a, &mut b);
+        assert_eq!(a.behavior, bolt(0, 2).behavior);
+        assert_eq!(a.behavior, ImpactBehavior::Ricochet { bounces_left: 2 });
+        assert_eq!(a.radius, bolt(0, 2).radius);
+
+        let (mut a, mut b) = (bolt(0, 3), bolt(1, 1));
+        resolve_clash(&mut a, &mut b);
+        assert_eq!(a.level, 1);
+        assert_eq!(a.behavior, ImpactBehavior::Destroy);
+    }
+}
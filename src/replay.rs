@@ -0,0 +1,175 @@
+//! Recorded matches.
+//!
+//! A replay is the tower the match was played on plus every frame's length and both
+//! wizards' [`PlayerInput`]s. The game plays the same given the same inputs and frame times,
+//! so feeding them back in redraws the match exactly. `--record FILE` saves one at the end of
+//! every round and `--replay FILE` plays it back. Stored as JSON like levels and settings.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::input::PlayerInput;
+use crate::level::LevelFile;
+use crate::stage::{self, Stage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    /// Seconds, as handed to the game's update.
+    pub dt: f32,
+    pub inputs: [PlayerInput; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Name of the stage, as in `stage.rs`.
+    pub stage: String,
+    pub level: LevelFile,
+    pub score_to_win: i32,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnknownStage(String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "couldn't read replay file: {e}"),
+            ReplayError::Parse(e) => write!(f, "replay file is malformed: {e}"),
+            ReplayError::UnknownStage(name) => write!(f, "replay is on a stage this build doesn't have: {name}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(e: std::io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(e: serde_json::Error) -> Self {
+        ReplayError::Parse(e)
+    }
+}
+
+impl Replay {
+    pub fn new(stage: &Stage, level: LevelFile, score_to_win: i32) -> Self {
+        Self { stage: stage.name.to_string(), level, score_to_win, frames: Vec::new() }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let text = std::fs::read_to_string(path)?;
+        let replay: Replay = serde_json::from_str(&text)?;
+        replay.stage()?;
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The stage the match was played on.
+    pub fn stage(&self) -> Result<Stage, ReplayError> {
+        stage::all().into_iter()
+            .find(|s| s.name == self.stage)
+            .ok_or_else(|| ReplayError::UnknownStage(self.stage.clone()))
+    }
+}
+
+/// A match being written down as it's played.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    pub path: PathBuf,
+    pub replay: Replay,
+}
+
+impl Recorder {
+    pub fn record(&mut self, frame: ReplayFrame) {
+        self.replay.frames.push(frame);
+    }
+
+    /// Writes what's been played so far, logging rather than failing.
+    pub fn save(&self) {
+        match self.replay.save(&self.path) {
+            Ok(()) => tracing::info!("saved replay to {}", self.path.display()),
+            Err(e) => tracing::warn!("{}: {e}", self.path.display()),
+        }
+    }
+
+    /// The same recording started over, for a restarted match.
+    pub fn restarted(&self) -> Self {
+        Self { path: self.path.clone(), replay: Replay { frames: Vec::new(), ..self.replay.clone() } }
+    }
+}
+
+/// A recorded match being fed back in.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    replay: Replay,
+    next: usize,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, next: 0 }
+    }
+
+    /// The next frame, or `None` once the recording runs out.
+    pub fn next_frame(&mut self) -> Option<ReplayFrame> {
+        let frame = self.replay.frames.get(self.next).copied();
+        self.next += 1;
+        frame
+    }
+
+    pub fn restarted(&self) -> Self {
+        Self::new(self.replay.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tower_gen;
+
+    #[test]
+    fn round_trips_through_json() {
+        let stage = stage::rooftops();
+        let mut recorder = Recorder {
+            path: std::env::temp_dir().join(format!("wizard-duel-replay-{}.json", std::process::id())),
+            replay: Replay::new(&stage, tower_gen::generate(7, &stage), 3),
+        };
+        let jump = PlayerInput { direction: -1.0, jump_pressed: true, jump_held: true, aim: Some((0.6, -0.8)), ..PlayerInput::default() };
+        recorder.record(ReplayFrame { dt: 1.0 / 60.0, inputs: [jump, PlayerInput::default()] });
+        recorder.record(ReplayFrame { dt: 1.0 / 144.0, inputs: [PlayerInput::default(), jump] });
+        recorder.replay.save(&recorder.path).unwrap();
+
+        let loaded = Replay::load(&recorder.path).unwrap();
+        assert_eq!(loaded, recorder.replay);
+        assert_eq!(loaded.stage().unwrap().name, stage.name);
+        std::fs::remove_file(&recorder.path).unwrap();
+
+        let mut playback = Playback::new(loaded);
+        assert_eq!(playback.next_frame().unwrap().inputs[0], jump);
+        assert_eq!(playback.next_frame().unwrap().dt, 1.0 / 144.0);
+        assert_eq!(playback.next_frame(), None);
+    }
+
+    #[test]
+    fn unknown_stage_is_refused() {
+        let stage = stage::courtyard();
+        let mut replay = Replay::new(&stage, tower_gen::generate(1, &stage), 5);
+        replay.stage = "moon base".to_string();
+        assert!(matches!(replay.stage(), Err(ReplayError::UnknownStage(_))));
+    }
+}
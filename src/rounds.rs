@@ -0,0 +1,196 @@
+//! How a match is split into rounds.
+//!
+//! A duel runs countdown, fight, finishing blow, intermission, and then the tower is rebuilt
+//! and both wizards start again from their spawn points. The timings, and whether a kill ends
+//! the round at all, come from [`RoundRules`] so each game mode can pace things its own way.
+
+/// How long "DUEL!" stays up once the countdown runs out.
+pub const DUEL_BANNER_TIME: f32 = 0.8;
+
+/// Pacing for a game mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundRules {
+    /// Seconds of 3-2-1 before each round. 0 skips straight to the fight.
+    pub countdown: f32,
+    /// A kill ends the round and resets the tower. Otherwise the loser respawns and the
+    /// climb carries on.
+    pub ends_on_kill: bool,
+    /// Seconds the finishing blow plays out in slow motion.
+    pub slow_motion: f32,
+    /// Game speed during the slow motion.
+    pub slow_motion_scale: f32,
+    /// Seconds the scoring banner holds the frozen fight before the next round.
+    pub intermission: f32,
+}
+
+impl RoundRules {
+    /// The normal match: a proper round for every point.
+    pub fn duel() -> Self {
+        Self { countdown: 3.0, ends_on_kill: true, slow_motion: 1.2, slow_motion_scale: 0.25, intermission: 2.0 }
+    }
+
+    /// One long climb with instant respawns, for trying out levels from the editor.
+    pub fn practice() -> Self {
+        Self { countdown: 0.0, ends_on_kill: false, slow_motion: 0.0, slow_motion_scale: 1.0, intermission: 0.0 }
+    }
+}
+
+impl Default for RoundRules {
+    fn default() -> Self {
+        Self::duel()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundPhase {
+    Countdown,
+    Fighting,
+    /// Someone scored - slow motion, then the banner.
+    Over { scorer: usize },
+}
+
+/// What the game should do with a frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tick {
+    /// Nothing moves.
+    Frozen,
+    /// Run the game for this long, which may be slowed down.
+    Run(f32),
+    /// The intermission is over - on to the next round, or the end of the match.
+    NextRound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Round {
+    /// Counting from 1.
+    pub number: u32,
+    pub phase: RoundPhase,
+    /// Seconds spent in the current phase.
+    pub timer: f32,
+}
+
+impl Round {
+    pub fn start(number: u32, rules: &RoundRules) -> Self {
+        let phase = if rules.countdown > 0.0 { RoundPhase::Countdown } else { RoundPhase::Fighting };
+        // skipping the countdown skips the "DUEL!" too
+        let timer = if phase == RoundPhase::Fighting { DUEL_BANNER_TIME } else { 0.0 };
+        Self { number, phase, timer }
+    }
+
+    pub fn is_fighting(&self) -> bool {
+        self.phase == RoundPhase::Fighting
+    }
+
+    /// Moves the round along by `dt` and says how much of it the game gets to run.
+    pub fn tick(&mut self, dt: f32, rules: &RoundRules) -> Tick {
+        self.timer += dt;
+        match self.phase {
+            RoundPhase::Countdown => {
+                if self.timer < rules.countdown { return Tick::Frozen; }
+                self.phase = RoundPhase::Fighting;
+                self.timer = 0.0;
+                Tick::Run(dt)
+            }
+            RoundPhase::Fighting => Tick::Run(dt),
+            RoundPhase::Over { .. } => {
+                if self.timer < rules.slow_motion {
+                    Tick::Run(dt * rules.slow_motion_scale)
+                } else if self.timer < rules.slow_motion + rules.intermission {
+                    Tick::Frozen
+                } else {
+                    Tick::NextRound
+                }
+            }
+        }
+    }
+
+    /// Ends the round in `scorer`'s favour. Only the first kill of a round counts, so this
+    /// returns false if the round was already decided.
+    pub fn finish(&mut self, scorer: usize) -> bool {
+        if !self.is_fighting() { return false; }
+        self.phase = RoundPhase::Over { scorer };
+        self.timer = 0.0;
+        true
+    }
+
+    /// Big centre-screen text for the start of the round: 3, 2, 1, then "DUEL!".
+    pub fn countdown_text(&self, rules: &RoundRules) -> Option<String> {
+        match self.phase {
+            RoundPhase::Countdown => Some(((rules.countdown - self.timer).ceil().max(1.0) as i32).to_string()),
+            RoundPhase::Fighting if self.timer < DUEL_BANNER_TIME => Some("DUEL!".to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn countdown_holds_the_fight_until_it_runs_out() {
+        let rules = RoundRules::duel();
+        let mut round = Round::start(1, &rules);
+        assert_eq!(round.phase, RoundPhase::Countdown);
+        assert_eq!(round.countdown_text(&rules).as_deref(), Some("3"));
+
+        assert_eq!(round.tick(1.5, &rules), Tick::Frozen);
+        assert_eq!(round.countdown_text(&rules).as_deref(), Some("2"));
+        assert_eq!(round.tick(1.0, &rules), Tick::Frozen);
+        assert_eq!(round.countdown_text(&rules).as_deref(), Some("1"));
+        assert!(!round.is_fighting());
+
+        // landing exactly on the end of the countdown starts the fight that frame
+        assert_eq!(round.tick(0.5, &rules), Tick::Run(0.5));
+        assert!(round.is_fighting());
+        assert_eq!(round.timer, 0.0);
+        assert_eq!(round.countdown_text(&rules).as_deref(), Some("DUEL!"));
+
+        round.tick(DUEL_BANNER_TIME, &rules);
+        assert_eq!(round.countdown_text(&rules), None);
+    }
+
+    #[test]
+    fn only_the_first_kill_counts() {
+        let rules = RoundRules::duel();
+        let mut round = Round::start(1, &rules);
+        // nothing to finish before the fight starts
+        assert!(!round.finish(0));
+
+        round.tick(rules.countdown, &rules);
+        assert!(round.finish(1));
+        assert_eq!(round.phase, RoundPhase::Over { scorer: 1 });
+        assert!(!round.finish(0));
+        assert_eq!(round.phase, RoundPhase::Over { scorer: 1 });
+    }
+
+    #[test]
+    fn finishing_blow_slows_down_then_holds_then_moves_on() {
+        let rules = RoundRules::duel();
+        let mut round = Round::start(3, &rules);
+        round.tick(rules.countdown, &rules);
+        round.finish(0);
+
+        assert_eq!(round.tick(0.1, &rules), Tick::Run(0.1 * rules.slow_motion_scale));
+        round.tick(rules.slow_motion, &rules);
+        assert_eq!(round.tick(0.1, &rules), Tick::Frozen);
+        round.tick(rules.intermission, &rules);
+        assert_eq!(round.tick(0.1, &rules), Tick::NextRound);
+        // the round number is the scene's to bump
+        assert_eq!(round.number, 3);
+    }
+
+    #[test]
+    fn practice_skips_the_countdown_and_the_banner() {
+        let rules = RoundRules::practice();
+        let mut round = Round::start(1, &rules);
+        assert!(round.is_fighting());
+        assert_eq!(round.countdown_text(&rules), None);
+        assert_eq!(round.tick(0.25, &rules), Tick::Run(0.25));
+        assert_eq!(round.countdown_text(&rules), None);
+
+        // with no slow motion or intermission a decided round moves straight on
+        round.finish(1);
+        assert_eq!(round.tick(0.016, &rules), Tick::NextRound);
+    }
+}
@@ -7,6 +7,8 @@ pub enum SceneSwitch {
     Push(Box<dyn Scene>),
     Replace(Box<dyn Scene>),
     Pop,
+    // pops this scene, then hands the switch to the one underneath
+    PopThen(Box<SceneSwitch>),
     Quit,
 }
 
@@ -26,9 +28,14 @@ pub trait Scene {
         SceneSwitch::None
     }
 
-    // strictly for rendering to the screen
+    // strictly for rendering - everything lands on the 1920x1080 canvas, see canvas.rs
     fn draw(&self, d: &mut RaylibDrawHandle, data: &mut GameData);
 
+    // overlays like the pause menu get the scene underneath drawn first, frozen in place
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
     // cleanup logic for when the scene is killed
     fn on_exit(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) {}
 }
\ No newline at end of file
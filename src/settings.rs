@@ -0,0 +1,506 @@
+//! Player preferences that stick between runs.
+//!
+//! Display mode, frame pacing, volumes, screen shake and keyboard/mouse bindings live in a
+//! [`Settings`] kept in `GameData`. They're saved as JSON in the user's config directory
+//! (`$XDG_CONFIG_HOME/wizard-duel/settings.json`, or `~/.config/...` when that isn't set)
+//! and applied to the window and audio at startup. A missing file means defaults; a broken
+//! one is logged and ignored.
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::audio::{AudioManager, VolumeCategory, Volumes};
+
+/// Window size in windowed mode when no resolution is picked.
+pub const WINDOWED_WIDTH: i32 = 1280;
+pub const WINDOWED_HEIGHT: i32 = 720;
+
+const CONFIG_DIR: &str = "wizard-duel";
+const CONFIG_FILE: &str = "settings.json";
+
+/// Resolutions on offer besides the desktop's own.
+pub const RESOLUTIONS: [(i32, i32); 5] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440), (3840, 2160)];
+/// Frame rate caps on offer. 0 is uncapped.
+pub const FPS_CAPS: [u32; 6] = [30, 60, 120, 144, 240, 0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayMode {
+    Windowed,
+    /// An undecorated window covering the whole monitor.
+    Borderless,
+    /// Exclusive fullscreen, switching the monitor to the picked resolution.
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "WINDOWED",
+            DisplayMode::Borderless => "BORDERLESS",
+            DisplayMode::Fullscreen => "FULLSCREEN",
+        }
+    }
+}
+
+/// Everything a wizard can be told to do from the keyboard or mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Left,
+    Right,
+    Jump,
+    Dash,
+    Drop,
+    Cast,
+    Parry,
+    Shield,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::Left, Action::Right, Action::Jump, Action::Dash,
+        Action::Drop, Action::Cast, Action::Parry, Action::Shield,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Left => "MOVE LEFT",
+            Action::Right => "MOVE RIGHT",
+            Action::Jump => "JUMP",
+            Action::Dash => "DASH",
+            Action::Drop => "DROP DOWN",
+            Action::Cast => "CAST",
+            Action::Parry => "PARRY",
+            Action::Shield => "SHIELD",
+        }
+    }
+}
+
+/// A key or mouse button, stored as raylib's code for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Binding {
+    Key(i32),
+    Mouse(i32),
+}
+
+// every mouse button raylib knows, for turning codes back into buttons
+const MOUSE_BUTTONS: [MouseButton; 7] = [
+    MouseButton::MOUSE_BUTTON_LEFT, MouseButton::MOUSE_BUTTON_RIGHT, MouseButton::MOUSE_BUTTON_MIDDLE,
+    MouseButton::MOUSE_BUTTON_SIDE, MouseButton::MOUSE_BUTTON_EXTRA, MouseButton::MOUSE_BUTTON_FORWARD,
+    MouseButton::MOUSE_BUTTON_BACK,
+];
+
+impl Binding {
+    fn key(key: KeyboardKey) -> Self {
+        Binding::Key(key as i32)
+    }
+
+    fn mouse(button: MouseButton) -> Self {
+        Binding::Mouse(button as i32)
+    }
+
+    fn mouse_button(code: i32) -> Option<MouseButton> {
+        MOUSE_BUTTONS.into_iter().find(|b| *b as i32 == code)
+    }
+
+    pub fn down(&self, rl: &RaylibHandle) -> bool {
+        match *self {
+            Binding::Key(code) => key_from_i32(code).is_some_and(|k| rl.is_key_down(k)),
+            Binding::Mouse(code) => Self::mouse_button(code).is_some_and(|b| rl.is_mouse_button_down(b)),
+        }
+    }
+
+    pub fn pressed(&self, rl: &RaylibHandle) -> bool {
+        match *self {
+            Binding::Key(code) => key_from_i32(code).is_some_and(|k| rl.is_key_pressed(k)),
+            Binding::Mouse(code) => Self::mouse_button(code).is_some_and(|b| rl.is_mouse_button_pressed(b)),
+        }
+    }
+
+    /// Whatever key or mouse button went down this frame, for rebinding.
+    pub fn capture(rl: &mut RaylibHandle) -> Option<Self> {
+        if let Some(key) = rl.get_key_pressed() { return Some(Self::key(key)); }
+        MOUSE_BUTTONS.into_iter().find(|b| rl.is_mouse_button_pressed(*b)).map(Self::mouse)
+    }
+
+    pub fn is_key(&self, key: KeyboardKey) -> bool {
+        *self == Self::key(key)
+    }
+
+    /// Short name for menus, like "LEFT SHIFT" or "MOUSE RIGHT".
+    pub fn label(&self) -> String {
+        // raylib's own names are KEY_LEFT_SHIFT and MOUSE_BUTTON_RIGHT, so tidy those up
+        let name = match *self {
+            Binding::Key(code) => match key_from_i32(code) {
+                Some(key) => format!("{:?}", key).trim_start_matches("KEY_").to_string(),
+                None => format!("KEY {code}"),
+            },
+            Binding::Mouse(code) => match Self::mouse_button(code) {
+                Some(button) => format!("{:?}", button).replace("MOUSE_BUTTON_", "MOUSE_"),
+                None => format!("MOUSE {code}"),
+            },
+        };
+        name.replace('_', " ")
+    }
+}
+
+/// One player's keyboard and mouse layout. Gamepads aren't rebindable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    pub left: Vec<Binding>,
+    pub right: Vec<Binding>,
+    pub jump: Vec<Binding>,
+    pub dash: Vec<Binding>,
+    pub drop: Vec<Binding>,
+    pub cast: Vec<Binding>,
+    pub parry: Vec<Binding>,
+    pub shield: Vec<Binding>,
+}
+
+impl KeyBindings {
+    /// WASD on the left of the keyboard, aiming and casting with the mouse.
+    pub fn player1() -> Self {
+        use KeyboardKey::*;
+        Self {
+            left: vec![Binding::key(KEY_A)],
+            right: vec![Binding::key(KEY_D)],
+            jump: vec![Binding::key(KEY_W), Binding::key(KEY_SPACE)],
+            dash: vec![Binding::key(KEY_Q)],
+            drop: vec![Binding::key(KEY_S)],
+            cast: vec![Binding::mouse(MouseButton::MOUSE_BUTTON_LEFT)],
+            parry: vec![Binding::mouse(MouseButton::MOUSE_BUTTON_RIGHT)],
+            shield: vec![Binding::key(KEY_LEFT_SHIFT)],
+        }
+    }
+
+    /// Arrow keys and the keys around them.
+    pub fn player2() -> Self {
+        use KeyboardKey::*;
+        Self {
+            left: vec![Binding::key(KEY_LEFT)],
+            right: vec![Binding::key(KEY_RIGHT)],
+            jump: vec![Binding::key(KEY_UP), Binding::key(KEY_RIGHT_CONTROL)],
+            dash: vec![Binding::key(KEY_PERIOD)],
+            drop: vec![Binding::key(KEY_DOWN)],
+            cast: vec![Binding::key(KEY_ENTER)],
+            parry: vec![Binding::key(KEY_RIGHT_SHIFT)],
+            shield: vec![Binding::key(KEY_SLASH)],
+        }
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        match action {
+            Action::Left => &self.left,
+            Action::Right => &self.right,
+            Action::Jump => &self.jump,
+            Action::Dash => &self.dash,
+            Action::Drop => &self.drop,
+            Action::Cast => &self.cast,
+            Action::Parry => &self.parry,
+            Action::Shield => &self.shield,
+        }
+    }
+
+    fn get_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+        match action {
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+            Action::Jump => &mut self.jump,
+            Action::Dash => &mut self.dash,
+            Action::Drop => &mut self.drop,
+            Action::Cast => &mut self.cast,
+            Action::Parry => &mut self.parry,
+            Action::Shield => &mut self.shield,
+        }
+    }
+
+    // takes `binding` off every action
+    fn unbind(&mut self, binding: Binding) {
+        for action in Action::ALL {
+            self.get_mut(action).retain(|b| *b != binding);
+        }
+    }
+
+    pub fn down(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.get(action).iter().any(|b| b.down(rl))
+    }
+
+    pub fn pressed(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.get(action).iter().any(|b| b.pressed(rl))
+    }
+
+    /// Every binding for `action`, like "W / SPACE", or a dash if there are none.
+    pub fn label(&self, action: Action) -> String {
+        let bindings = self.get(action);
+        if bindings.is_empty() { return "-".to_string(); }
+        bindings.iter().map(|b| b.label()).collect::<Vec<_>>().join(" / ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub display: DisplayMode,
+    /// `None` uses the desktop resolution.
+    pub resolution: Option<(i32, i32)>,
+    pub vsync: bool,
+    /// 0 is uncapped.
+    pub fps_cap: u32,
+    pub volumes: Volumes,
+    /// How hard the camera shakes, 0 (off) to 1.
+    pub screen_shake: f32,
+    /// Keyboard and mouse layout for player 1 and player 2.
+    pub controls: [KeyBindings; 2],
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            display: DisplayMode::Fullscreen,
+            resolution: None,
+            vsync: true,
+            fps_cap: 0,
+            volumes: Volumes::default(),
+            screen_shake: 1.0,
+            controls: [KeyBindings::player1(), KeyBindings::player2()],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    /// Neither `XDG_CONFIG_HOME` nor `HOME` is set.
+    NoConfigDir,
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(e) => write!(f, "couldn't read settings file: {e}"),
+            SettingsError::Parse(e) => write!(f, "settings file is malformed: {e}"),
+            SettingsError::NoConfigDir => write!(f, "no config directory to keep settings in"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<std::io::Error> for SettingsError {
+    fn from(e: std::io::Error) -> Self {
+        SettingsError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SettingsError {
+    fn from(e: serde_json::Error) -> Self {
+        SettingsError::Parse(e)
+    }
+}
+
+/// Where settings are kept, following the XDG base directory spec.
+pub fn config_path() -> Option<PathBuf> {
+    config_path_from(std::env::var_os("XDG_CONFIG_HOME"), std::env::var_os("HOME"))
+}
+
+fn config_path_from(xdg_config_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    // the spec says relative paths in XDG_CONFIG_HOME are to be ignored
+    let base = xdg_config_home
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home.map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join(CONFIG_DIR).join(CONFIG_FILE))
+}
+
+impl Settings {
+    /// The saved settings, or defaults if there aren't any or they can't be read.
+    pub fn load() -> Self {
+        let Some(path) = config_path() else { return Self::default() };
+        if !path.exists() { return Self::default(); }
+        Self::load_from(&path).unwrap_or_else(|e| {
+            tracing::warn!("{}: {e}, using default settings", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, SettingsError> {
+        let text = std::fs::read_to_string(path)?;
+        let mut settings: Self = serde_json::from_str(&text)?;
+        settings.clamp();
+        Ok(settings)
+    }
+
+    // hand-edited files can hold anything, so pull the sliders back into range
+    fn clamp(&mut self) {
+        for category in [VolumeCategory::Master, VolumeCategory::Music, VolumeCategory::Sfx] {
+            let volume = self.volumes.get(category);
+            self.volumes.set(category, volume);
+        }
+        self.screen_shake = self.screen_shake.clamp(0.0, 1.0);
+    }
+
+    /// Makes `binding` the only way for `player` to do `action`. A key only does one thing,
+    /// so it's taken off whatever action had it before, for either player.
+    pub fn rebind(&mut self, player: usize, action: Action, binding: Binding) {
+        for controls in &mut self.controls {
+            controls.unbind(binding);
+        }
+        *self.controls[player].get_mut(action) = vec![binding];
+    }
+
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let path = config_path().ok_or(SettingsError::NoConfigDir)?;
+        self.save_to(path)
+    }
+
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Puts the window into the chosen mode and resolution.
+    pub fn apply_display(&self, rl: &mut RaylibHandle) {
+        // back to a plain window first so any mode can switch to any other
+        if rl.is_window_fullscreen() { rl.toggle_fullscreen(); }
+        rl.clear_window_state(WindowState::default().set_window_undecorated(true));
+
+        let monitor = get_current_monitor();
+        let desktop = (get_monitor_width(monitor), get_monitor_height(monitor));
+        let monitor_pos = get_monitor_position(monitor);
+        match self.display {
+            DisplayMode::Windowed => {
+                let (w, h) = self.resolution.unwrap_or((WINDOWED_WIDTH, WINDOWED_HEIGHT));
+                rl.set_window_size(w, h);
+                // centred on the monitor
+                rl.set_window_position(monitor_pos.x as i32 + (desktop.0 - w) / 2, monitor_pos.y as i32 + (desktop.1 - h) / 2);
+            }
+            DisplayMode::Borderless => {
+                rl.set_window_state(WindowState::default().set_window_undecorated(true));
+                rl.set_window_position(monitor_pos.x as i32, monitor_pos.y as i32);
+                rl.set_window_size(desktop.0, desktop.1);
+            }
+            DisplayMode::Fullscreen => {
+                let (w, h) = self.resolution.unwrap_or(desktop);
+                rl.set_window_size(w, h);
+                rl.toggle_fullscreen();
+            }
+        }
+    }
+
+    /// Turns vsync on or off and sets the frame rate cap.
+    pub fn apply_frame_pacing(&self, rl: &mut RaylibHandle) {
+        let vsync = WindowState::default().set_vsync_hint(true);
+        if self.vsync { rl.set_window_state(vsync); } else { rl.clear_window_state(vsync); }
+        rl.set_target_fps(self.fps_cap);
+    }
+
+    pub fn apply_audio(&self, audio: &mut AudioManager) {
+        for category in [VolumeCategory::Master, VolumeCategory::Music, VolumeCategory::Sfx] {
+            audio.set_volume(category, self.volumes.get(category));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a fresh directory per test, since they run in parallel
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wizard-duel-settings-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut settings = Settings {
+            display: DisplayMode::Borderless,
+            resolution: Some((1600, 900)),
+            vsync: false,
+            fps_cap: 144,
+            screen_shake: 0.5,
+            ..Settings::default()
+        };
+        settings.volumes.set(VolumeCategory::Music, 0.3);
+        settings.rebind(0, Action::Dash, Binding::key(KeyboardKey::KEY_E));
+
+        let dir = scratch_dir("round-trip");
+        let path = dir.join(CONFIG_DIR).join(CONFIG_FILE);
+        settings.save_to(&path).unwrap();
+        assert_eq!(Settings::load_from(&path).unwrap(), settings);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn partial_file_fills_in_defaults() {
+        let dir = scratch_dir("partial");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        std::fs::write(&path, r#"{ "vsync": false, "volumes": { "music": 0.2 } }"#).unwrap();
+
+        let settings = Settings::load_from(&path).unwrap();
+        let defaults = Settings::default();
+        assert!(!settings.vsync);
+        assert_eq!(settings.volumes.music, 0.2);
+        assert_eq!(settings.volumes.master, defaults.volumes.master);
+        assert_eq!(settings.display, defaults.display);
+        assert_eq!(settings.controls, defaults.controls);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped_on_load() {
+        let dir = scratch_dir("clamp");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        std::fs::write(&path, r#"{ "screen_shake": 12.0, "volumes": { "master": 3.0, "sfx": -1.0 } }"#).unwrap();
+
+        let settings = Settings::load_from(&path).unwrap();
+        assert_eq!(settings.screen_shake, 1.0);
+        assert_eq!(settings.volumes.master, 1.0);
+        assert_eq!(settings.volumes.sfx, 0.0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn relative_xdg_config_home_falls_back_to_home() {
+        let path = |xdg: Option<&str>, home: Option<&str>| config_path_from(xdg.map(OsString::from), home.map(OsString::from));
+        let in_home = Some(PathBuf::from("/home/wiz/.config/wizard-duel/settings.json"));
+
+        assert_eq!(path(Some("/xdg"), Some("/home/wiz")), Some(PathBuf::from("/xdg/wizard-duel/settings.json")));
+        assert_eq!(path(Some("relative/config"), Some("/home/wiz")), in_home);
+        assert_eq!(path(None, Some("/home/wiz")), in_home);
+        assert_eq!(path(Some("relative/config"), None), None);
+    }
+
+    #[test]
+    fn rebinding_takes_the_key_from_both_players() {
+        let mut settings = Settings::default();
+        let enter = Binding::key(KeyboardKey::KEY_ENTER);
+        assert!(settings.controls[1].get(Action::Cast).contains(&enter));
+
+        // player 1 grabs player 2's cast key for their jump
+        settings.rebind(0, Action::Jump, enter);
+        assert_eq!(settings.controls[0].get(Action::Jump), &[enter]);
+        assert!(settings.controls[1].get(Action::Cast).is_empty());
+
+        // and moving it again within player 1 leaves it in one place
+        settings.rebind(0, Action::Dash, enter);
+        assert!(settings.controls[0].get(Action::Jump).is_empty());
+        let holders = Action::ALL.iter()
+            .flat_map(|a| settings.controls.iter().map(move |c| c.get(*a)))
+            .filter(|bindings| bindings.contains(&enter))
+            .count();
+        assert_eq!(holders, 1);
+    }
+}
@@ -0,0 +1,323 @@
+use raylib::prelude::*;
+
+use crate::audio::VolumeCategory;
+use crate::canvas::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+use crate::game_data::GameData;
+use crate::scenes::{Scene, SceneSwitch};
+use crate::settings::{Action, Binding, DisplayMode, Settings, FPS_CAPS, RESOLUTIONS};
+
+// sliders move in tenths
+const SLIDER_STEP: f32 = 0.1;
+
+const GAMEPAD_HELP: &str = "GAMEPADS: left stick move  right stick aim  A jump  B dash  down drop  RT cast  RB parry  LT shield";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Row {
+    Display,
+    Resolution,
+    Vsync,
+    FpsCap,
+    Volume(VolumeCategory),
+    Shake,
+    Controls,
+    Reset,
+    Back,
+}
+
+const ROWS: [Row; 11] = [
+    Row::Display,
+    Row::Resolution,
+    Row::Vsync,
+    Row::FpsCap,
+    Row::Volume(VolumeCategory::Master),
+    Row::Volume(VolumeCategory::Music),
+    Row::Volume(VolumeCategory::Sfx),
+    Row::Shake,
+    Row::Controls,
+    Row::Reset,
+    Row::Back,
+];
+
+impl Row {
+    fn label(&self) -> &'static str {
+        match self {
+            Row::Display => "DISPLAY",
+            Row::Resolution => "RESOLUTION",
+            Row::Vsync => "VSYNC",
+            Row::FpsCap => "FPS CAP",
+            Row::Volume(VolumeCategory::Master) => "MASTER VOLUME",
+            Row::Volume(VolumeCategory::Music) => "MUSIC VOLUME",
+            Row::Volume(VolumeCategory::Sfx) => "EFFECTS VOLUME",
+            Row::Shake => "SCREEN SHAKE",
+            Row::Controls => "CONTROLS",
+            Row::Reset => "RESET TO DEFAULTS",
+            Row::Back => "BACK",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Page {
+    General,
+    Controls,
+}
+
+// desktop first, then the fixed list
+fn resolution_choices() -> Vec<Option<(i32, i32)>> {
+    std::iter::once(None).chain(RESOLUTIONS.map(Some)).collect()
+}
+
+// one step left or right through a list of choices, wrapping round
+fn cycle<T: PartialEq + Copy>(options: &[T], current: T, step: i32) -> T {
+    let i = options.iter().position(|o| *o == current).unwrap_or(0) as i32;
+    options[(i + step).rem_euclid(options.len() as i32) as usize]
+}
+
+// nudges a 0-1 slider and snaps it to the nearest tenth so repeated steps don't drift
+fn nudge(value: f32, step: i32) -> f32 {
+    ((value + step as f32 * SLIDER_STEP) * 10.0).round().clamp(0.0, 10.0) / 10.0
+}
+
+/// Display, audio, shake and key bindings. Everything applies as soon as it changes and is
+/// saved to the config file on the way out.
+pub struct SettingsScene {
+    page: Page,
+    cursor: usize,
+    // controls page: which action and which player's column
+    action_cursor: usize,
+    player_cursor: usize,
+    // waiting for a key or mouse button for the highlighted binding
+    rebinding: bool,
+    // opened on the bindings from the pause menu, so backing out leaves altogether
+    controls_only: bool,
+}
+
+impl SettingsScene {
+    pub fn new() -> Self {
+        Self { page: Page::General, cursor: 0, action_cursor: 0, player_cursor: 0, rebinding: false, controls_only: false }
+    }
+
+    /// Opens straight onto the key bindings.
+    pub fn controls() -> Self {
+        Self { page: Page::Controls, controls_only: true, ..Self::new() }
+    }
+
+    fn close(&self, data: &GameData) -> SceneSwitch {
+        if let Err(e) = data.settings.save() {
+            tracing::warn!("{e}, settings will reset next time");
+        }
+        SceneSwitch::Pop
+    }
+
+    // left/right on a row of the general page
+    fn adjust(&mut self, row: Row, step: i32, rl: &mut RaylibHandle, data: &mut GameData) {
+        let settings = &mut data.settings;
+        match row {
+            // picking a mode or size here takes over from the command line's
+            Row::Display => {
+                settings.display = cycle(&DisplayMode::ALL, data.launch.display_settings(settings).display, step);
+                data.launch.windowed = false;
+                data.launch.display_settings(settings).apply_display(rl);
+            }
+            Row::Resolution => {
+                settings.resolution = cycle(&resolution_choices(), data.launch.display_settings(settings).resolution, step);
+                data.launch.resolution = None;
+                // borderless always covers the desktop
+                if settings.display != DisplayMode::Borderless { data.launch.display_settings(settings).apply_display(rl); }
+            }
+            Row::Vsync => {
+                settings.vsync = !settings.vsync;
+                settings.apply_frame_pacing(rl);
+            }
+            Row::FpsCap => {
+                settings.fps_cap = cycle(&FPS_CAPS, settings.fps_cap, step);
+                settings.apply_frame_pacing(rl);
+            }
+            Row::Volume(category) => {
+                settings.volumes.set(category, nudge(settings.volumes.get(category), step));
+                settings.apply_audio(&mut data.audio);
+            }
+            Row::Shake => settings.screen_shake = nudge(settings.screen_shake, step),
+            Row::Controls | Row::Reset | Row::Back => (),
+        }
+    }
+
+    fn value(&self, row: Row, settings: &Settings) -> Option<String> {
+        let value = match row {
+            Row::Display => settings.display.label().to_string(),
+            Row::Resolution => match (settings.display, settings.resolution) {
+                (DisplayMode::Borderless, _) | (_, None) => "DESKTOP".to_string(),
+                (_, Some((w, h))) => format!("{w} x {h}"),
+            },
+            Row::Vsync => if settings.vsync { "ON" } else { "OFF" }.to_string(),
+            Row::FpsCap => match settings.fps_cap {
+                0 => "UNCAPPED".to_string(),
+                cap => cap.to_string(),
+            },
+            Row::Volume(category) => format!("{}%", (settings.volumes.get(category) * 100.0).round()),
+            Row::Shake => format!("{}%", (settings.screen_shake * 100.0).round()),
+            Row::Controls | Row::Reset | Row::Back => return None,
+        };
+        Some(value)
+    }
+
+    fn draw_general(&self, d: &mut RaylibDrawHandle, settings: &Settings) {
+        let center_x = VIRTUAL_WIDTH / 2;
+        for (i, row) in ROWS.iter().enumerate() {
+            let selected = i == self.cursor;
+            let color = if selected { Color::GOLD } else { Color::LIGHTGRAY };
+            let y = 200 + i as i32 * 66;
+            d.draw_text(row.label(), center_x - 520, y, 40, color);
+
+            let Some(value) = self.value(*row, settings) else { continue };
+            // sliders get a bar next to the number
+            let fill = match row {
+                Row::Volume(category) => Some(settings.volumes.get(*category)),
+                Row::Shake => Some(settings.screen_shake),
+                _ => None,
+            };
+            let value_x = match fill {
+                Some(fill) => {
+                    let bar = Rectangle::new(center_x as f32 + 60.0, y as f32 + 6.0, 300.0, 28.0);
+                    d.draw_rectangle_rec(Rectangle::new(bar.x, bar.y, bar.width * fill, bar.height), color);
+                    d.draw_rectangle_lines_ex(bar, 3.0, color);
+                    center_x + 390
+                }
+                None => center_x + 60,
+            };
+            let text = if selected { format!("< {} >", value) } else { value };
+            d.draw_text(&text, value_x, y, 40, color);
+        }
+
+        let hint = "UP / DOWN TO PICK - LEFT / RIGHT TO CHANGE - ESC TO SAVE AND GO BACK";
+        let hint_w = d.measure_text(hint, 24);
+        d.draw_text(hint, center_x - hint_w / 2, VIRTUAL_HEIGHT - 80, 24, Color::GRAY);
+    }
+
+    fn draw_controls(&self, d: &mut RaylibDrawHandle, settings: &Settings) {
+        let center_x = VIRTUAL_WIDTH / 2;
+        let columns = [center_x - 150, center_x + 300];
+        d.draw_text("PLAYER 1", columns[0], 190, 36, Color::WHITE);
+        d.draw_text("PLAYER 2", columns[1], 190, 36, Color::WHITE);
+
+        for (i, action) in Action::ALL.iter().enumerate() {
+            let y = 260 + i as i32 * 70;
+            let row_selected = i == self.action_cursor;
+            d.draw_text(action.label(), center_x - 650, y, 36, if row_selected { Color::WHITE } else { Color::LIGHTGRAY });
+
+            for (player, x) in columns.iter().enumerate() {
+                let selected = row_selected && player == self.player_cursor;
+                let label = if selected && self.rebinding {
+                    "PRESS A KEY...".to_string()
+                } else {
+                    settings.controls[player].label(*action)
+                };
+                d.draw_text(&label, *x, y, 32, if selected { Color::GOLD } else { Color::LIGHTGRAY });
+                if selected {
+                    let cell = Rectangle::new(*x as f32 - 14.0, y as f32 - 10.0, 430.0, 52.0);
+                    d.draw_rectangle_lines_ex(cell, 3.0, Color::GOLD);
+                }
+            }
+        }
+
+        let help_w = d.measure_text(GAMEPAD_HELP, 26);
+        d.draw_text(GAMEPAD_HELP, center_x - help_w / 2, VIRTUAL_HEIGHT - 150, 26, Color::LIGHTGRAY);
+
+        let hint = if self.rebinding {
+            "PRESS A KEY OR MOUSE BUTTON - ESC TO CANCEL"
+        } else {
+            "ARROWS TO PICK - ENTER TO REBIND - ESC TO SAVE AND GO BACK"
+        };
+        let hint_w = d.measure_text(hint, 24);
+        d.draw_text(hint, center_x - hint_w / 2, VIRTUAL_HEIGHT - 80, 24, Color::GRAY);
+    }
+}
+
+impl Default for SettingsScene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for SettingsScene {
+    fn handle_input(&mut self, rl: &mut RaylibHandle, data: &mut GameData) -> SceneSwitch {
+        let pad = |rl: &RaylibHandle, button| rl.is_gamepad_button_pressed(0, button) || rl.is_gamepad_button_pressed(1, button);
+
+        // waiting on a binding - anything but escape (or B on a pad) becomes the new one
+        if self.rebinding {
+            if pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT) {
+                self.rebinding = false;
+            } else if let Some(binding) = Binding::capture(rl) {
+                if !binding.is_key(KeyboardKey::KEY_ESCAPE) {
+                    data.settings.rebind(self.player_cursor, Action::ALL[self.action_cursor], binding);
+                }
+                self.rebinding = false;
+            }
+            return SceneSwitch::None;
+        }
+
+        let up = rl.is_key_pressed(KeyboardKey::KEY_UP) || rl.is_key_pressed(KeyboardKey::KEY_W) || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP);
+        let down = rl.is_key_pressed(KeyboardKey::KEY_DOWN) || rl.is_key_pressed(KeyboardKey::KEY_S) || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN);
+        let left = rl.is_key_pressed(KeyboardKey::KEY_LEFT) || rl.is_key_pressed(KeyboardKey::KEY_A) || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT);
+        let right = rl.is_key_pressed(KeyboardKey::KEY_RIGHT) || rl.is_key_pressed(KeyboardKey::KEY_D) || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT);
+        let confirm = rl.is_key_pressed(KeyboardKey::KEY_ENTER) || pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+        let back = rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) || rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE)
+            || pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT);
+        let step = if left { -1 } else if right { 1 } else { 0 };
+
+        match self.page {
+            Page::General => {
+                if back { return self.close(data); }
+                if up { self.cursor = (self.cursor + ROWS.len() - 1) % ROWS.len(); }
+                if down { self.cursor = (self.cursor + 1) % ROWS.len(); }
+                let row = ROWS[self.cursor];
+                if step != 0 { self.adjust(row, step, rl, data); }
+                if confirm {
+                    match row {
+                        Row::Vsync => self.adjust(row, 1, rl, data),
+                        Row::Controls => self.page = Page::Controls,
+                        Row::Reset => {
+                            data.settings = Settings::default();
+                            data.launch.windowed = false;
+                            data.launch.resolution = None;
+                            data.apply_display(rl);
+                            data.settings.apply_frame_pacing(rl);
+                            data.settings.apply_audio(&mut data.audio);
+                        }
+                        Row::Back => return self.close(data),
+                        _ => (),
+                    }
+                }
+            }
+            Page::Controls => {
+                if back {
+                    if self.controls_only { return self.close(data); }
+                    self.page = Page::General;
+                }
+                let actions = Action::ALL.len();
+                if up { self.action_cursor = (self.action_cursor + actions - 1) % actions; }
+                if down { self.action_cursor = (self.action_cursor + 1) % actions; }
+                if step != 0 { self.player_cursor = 1 - self.player_cursor; }
+                if confirm { self.rebinding = true; }
+            }
+        }
+        SceneSwitch::None
+    }
+
+    fn draw(&self, d: &mut RaylibDrawHandle, data: &mut GameData) {
+        d.clear_background(Color::new(20, 20, 30, 255));
+
+        let title = match self.page {
+            Page::General => "SETTINGS",
+            Page::Controls => "CONTROLS",
+        };
+        let title_w = d.measure_text(title, 70);
+        d.draw_text(title, VIRTUAL_WIDTH / 2 - title_w / 2, 70, 70, Color::WHITE);
+
+        match self.page {
+            // shows the window as it is, command-line flags and all
+            Page::General => self.draw_general(d, &data.launch.display_settings(&data.settings)),
+            Page::Controls => self.draw_controls(d, &data.settings),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Themed stages.
+//!
+//! A stage is everything about a run that isn't the platform layout: which backgrounds and
+//! tiles it's drawn with, the hazard the generator sprinkles on platforms, the music, how fast
+//! the lava chases the wizards and what the rooftop arena looks like.
+use raylib::prelude::*;
+
+use crate::audio::{AdaptiveTrack, Layer};
+use crate::level::{ArenaLayout, BlockDef, HazardDef, HazardType, PlatformDef, PlatformType, SpawnPoint};
+use crate::parallax::{Parallax, ParallaxLayer, SkyBand};
+
+/// How the camera speeds up on the climb: starts at `start`, grows by `growth` of itself
+/// every second and never passes `max` (units per second).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollCurve {
+    pub start: f32,
+    pub growth: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Stage {
+    pub name: &'static str,
+    pub background: Parallax,
+    /// Indices into `GameData::obstacle_tex_vec`.
+    pub tileset: Vec<usize>,
+    pub hazard: HazardType,
+    pub music: AdaptiveTrack,
+    pub scroll: ScrollCurve,
+    pub rooftop: ArenaLayout,
+}
+
+/// Every stage, in the order the select screen shows them.
+pub fn all() -> Vec<Stage> {
+    vec![courtyard(), rooftops()]
+}
+
+/// The original tower: a garden courtyard with spikes and a calm climb.
+pub fn courtyard() -> Stage {
+    Stage {
+        name: "Courtyard",
+        // far castle wall tiles up forever and fades into a dusk and then night sky,
+        // the fountain courtyard falls away below
+        background: Parallax {
+            layers: vec![
+                ParallaxLayer {
+                    texture: 0,
+                    source: Rectangle::new(0.0, 0.0, 576.0, 160.0),
+                    rate: 0.15,
+                    repeat: true,
+                    fade_out: 0.7,
+                    tint: Color::new(170, 170, 190, 255),
+                },
+                ParallaxLayer {
+                    texture: 0,
+                    source: Rectangle::new(0.0, 0.0, 576.0, 324.0),
+                    rate: 0.5,
+                    repeat: false,
+                    fade_out: 2.0,
+                    tint: Color::WHITE,
+                },
+            ],
+            sky: vec![
+                SkyBand { at: 0.0, top: Color::new(135, 170, 200, 255), bottom: Color::new(200, 220, 230, 255) },
+                SkyBand { at: 0.5, top: Color::new(120, 70, 110, 255), bottom: Color::new(250, 150, 90, 255) },
+                SkyBand { at: 1.0, top: Color::new(15, 15, 45, 255), bottom: Color::new(60, 40, 90, 255) },
+            ],
+        },
+        tileset: vec![0, 1, 2],
+        hazard: HazardType::Spikes,
+        music: AdaptiveTrack {
+            base: "./resources/music/courtyard_base.wav",
+            layers: vec![
+                (Layer::Drive, "./resources/music/courtyard_drive.wav"),
+                (Layer::Peak, "./resources/music/courtyard_peak.wav"),
+                (Layer::Tension, "./resources/music/tension.wav"),
+            ],
+        },
+        scroll: ScrollCurve { start: 60.0, growth: 0.09, max: 250.0 },
+        rooftop: ArenaLayout {
+            floor_offset: 400.0,
+            platforms: Vec::new(),
+            // a pair of pillars on the roof so there's something to duck behind
+            solids: vec![
+                BlockDef { x: 620.0, y: 220.0, width: 64.0, height: 180.0 },
+                BlockDef { x: 1236.0, y: 220.0, width: 64.0, height: 180.0 },
+            ],
+            hazards: Vec::new(),
+            spawn_points: vec![SpawnPoint { x: 600.0, y: 400.0 }, SpawnPoint { x: 1320.0, y: 400.0 }],
+        },
+    }
+}
+
+/// Red-tiled rooftops: faster lava, burning chimneys and a split-level arena.
+pub fn rooftops() -> Stage {
+    Stage {
+        name: "Rooftops",
+        // drifting clouds over endless red tiles, with the gatehouse dropping out of view
+        background: Parallax {
+            layers: vec![
+                ParallaxLayer {
+                    texture: 1,
+                    source: Rectangle::new(0.0, 0.0, 576.0, 60.0),
+                    rate: 0.05,
+                    repeat: true,
+                    fade_out: 2.0,
+                    tint: Color::WHITE.alpha(0.8),
+                },
+                ParallaxLayer {
+                    texture: 1,
+                    source: Rectangle::new(0.0, 70.0, 576.0, 170.0),
+                    rate: 0.2,
+                    repeat: true,
+                    fade_out: 0.6,
+                    tint: Color::new(200, 180, 180, 255),
+                },
+                ParallaxLayer {
+                    texture: 1,
+                    source: Rectangle::new(0.0, 0.0, 576.0, 324.0),
+                    rate: 0.5,
+                    repeat: false,
+                    fade_out: 2.0,
+                    tint: Color::WHITE,
+                },
+            ],
+            sky: vec![
+                SkyBand { at: 0.0, top: Color::new(240, 230, 200, 255), bottom: Color::new(230, 215, 180, 255) },
+                SkyBand { at: 0.6, top: Color::new(200, 80, 80, 255), bottom: Color::new(255, 140, 80, 255) },
+                SkyBand { at: 1.0, top: Color::new(40, 20, 60, 255), bottom: Color::new(120, 50, 80, 255) },
+            ],
+        },
+        tileset: vec![3, 1],
+        hazard: HazardType::Flames,
+        music: AdaptiveTrack {
+            base: "./resources/music/rooftops_base.wav",
+            layers: vec![
+                (Layer::Drive, "./resources/music/rooftops_drive.wav"),
+                (Layer::Peak, "./resources/music/rooftops_peak.wav"),
+                (Layer::Tension, "./resources/music/tension.wav"),
+            ],
+        },
+        scroll: ScrollCurve { start: 80.0, growth: 0.11, max: 300.0 },
+        rooftop: ArenaLayout {
+            floor_offset: 400.0,
+            platforms: vec![
+                PlatformDef { x: 460.0, y: 200.0, width: 240.0, kind: PlatformType::Static, texture: None },
+                PlatformDef { x: 1220.0, y: 200.0, width: 240.0, kind: PlatformType::Static, texture: None },
+                PlatformDef { x: 800.0, y: 40.0, width: 320.0, kind: PlatformType::Static, texture: None },
+            ],
+            solids: vec![BlockDef { x: 928.0, y: 300.0, width: 64.0, height: 100.0 }],
+            hazards: vec![
+                HazardDef { kind: HazardType::Flames, x: 300.0, y: 370.0, width: 120.0, height: 30.0 },
+                HazardDef { kind: HazardType::Flames, x: 1500.0, y: 370.0, width: 120.0, height: 30.0 },
+            ],
+            spawn_points: vec![SpawnPoint { x: 600.0, y: 400.0 }, SpawnPoint { x: 1320.0, y: 400.0 }],
+        },
+    }
+}
@@ -0,0 +1,132 @@
+use rand::Rng;
+use raylib::prelude::*;
+
+use crate::audio::MENU_MUSIC;
+use crate::canvas::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+use crate::game_data::GameData;
+use crate::game_scene::GameScene;
+use crate::level::{HazardType, LevelFile, TOWER_LEVEL_PATH};
+use crate::scenes::{Scene, SceneSwitch};
+use crate::stage::{self, Stage};
+
+pub struct StageSelectScene {
+    stages: Vec<Stage>,
+    // one past the last stage is the random pick
+    cursor: usize,
+}
+
+impl StageSelectScene {
+    pub fn new() -> Self {
+        Self { stages: stage::all(), cursor: 0 }
+    }
+
+    fn start(&self, data: &mut GameData) -> SceneSwitch {
+        let stage = match self.stages.get(self.cursor) {
+            Some(stage) => stage.clone(),
+            None => self.stages[rand::rng().random_range(0..self.stages.len())].clone(),
+        };
+
+        // reset scores for the fresh run
+        data.player_scores = vec![0, 0];
+        // a hand-authored tower wins over the generated one if it's there
+        let level = LevelFile::load_or_generate(TOWER_LEVEL_PATH, data.tower_seed, &stage);
+        SceneSwitch::Replace(Box::new(GameScene::from_level(level, stage)))
+    }
+}
+
+impl Default for StageSelectScene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for StageSelectScene {
+    fn handle_input(&mut self, rl: &mut RaylibHandle, data: &mut GameData) -> SceneSwitch {
+        let options = self.stages.len() + 1;
+        let pad = |rl: &RaylibHandle, button| rl.is_gamepad_button_pressed(0, button) || rl.is_gamepad_button_pressed(1, button);
+
+        if rl.is_key_pressed(KeyboardKey::KEY_LEFT) || rl.is_key_pressed(KeyboardKey::KEY_A)
+            || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT) {
+            self.cursor = (self.cursor + options - 1) % options;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) || rl.is_key_pressed(KeyboardKey::KEY_D)
+            || pad(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT) {
+            self.cursor = (self.cursor + 1) % options;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER)
+            || pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN)
+            || pad(rl, GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT) {
+            return self.start(data);
+        }
+
+        // back out to the title
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || pad(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT) {
+            return SceneSwitch::Pop;
+        }
+        SceneSwitch::None
+    }
+
+    fn update(&mut self, _dt: f32, data: &mut GameData) -> SceneSwitch {
+        data.audio.play_music(MENU_MUSIC);
+        SceneSwitch::None
+    }
+
+    fn draw(&self, d: &mut RaylibDrawHandle, data: &mut GameData) {
+        d.clear_background(Color::BLACK);
+        let screen_w = VIRTUAL_WIDTH as f32;
+        let screen_h = VIRTUAL_HEIGHT as f32;
+
+        let title = "CHOOSE A STAGE";
+        let title_size = 70;
+        let title_w = d.measure_text(title, title_size);
+        d.draw_text(title, (screen_w / 2.0) as i32 - title_w / 2, (screen_h * 0.12) as i32, title_size, Color::WHITE);
+
+        // one card per stage plus the random pick, centred as a row
+        let options = self.stages.len() + 1;
+        let card_w = 480.0;
+        let card_h = 270.0;
+        let gap = 40.0;
+        let row_w = options as f32 * card_w + (options - 1) as f32 * gap;
+        let top = screen_h / 2.0 - card_h / 2.0;
+
+        for i in 0..options {
+            let selected = i == self.cursor;
+            let x = screen_w / 2.0 - row_w / 2.0 + i as f32 * (card_w + gap);
+            let card = Rectangle::new(x, top, card_w, card_h);
+
+            let (name, details) = match self.stages.get(i) {
+                Some(stage) => {
+                    // stage preview from its first background
+                    if let Some(bg) = stage.background.preview().and_then(|b| data.background_tex_vec.get(b)) {
+                        let tint = if selected { Color::WHITE } else { Color::GRAY };
+                        d.draw_texture_pro(bg, Rectangle::new(0.0, 0.0, bg.width as f32, bg.height as f32), card, Vector2::zero(), 0.0, tint);
+                    }
+                    let hazard = match stage.hazard {
+                        HazardType::Spikes => "spikes",
+                        HazardType::Flames => "flames",
+                    };
+                    (stage.name.to_string(), format!("hazard: {}  top speed: {}", hazard, stage.scroll.max as i32))
+                }
+                None => {
+                    d.draw_rectangle_rec(card, Color::new(40, 40, 55, 255));
+                    let q_w = d.measure_text("?", 160);
+                    d.draw_text("?", (x + card_w / 2.0) as i32 - q_w / 2, (top + 50.0) as i32, 160, Color::LIGHTGRAY);
+                    ("Random".to_string(), "let fate decide".to_string())
+                }
+            };
+
+            let outline = if selected { Color::GOLD } else { Color::DARKGRAY };
+            d.draw_rectangle_lines_ex(card, if selected { 6.0 } else { 2.0 }, outline);
+
+            let name_w = d.measure_text(&name, 40);
+            d.draw_text(&name, (x + card_w / 2.0) as i32 - name_w / 2, (top + card_h + 20.0) as i32, 40, if selected { Color::GOLD } else { Color::WHITE });
+            let details_w = d.measure_text(&details, 20);
+            d.draw_text(&details, (x + card_w / 2.0) as i32 - details_w / 2, (top + card_h + 70.0) as i32, 20, Color::GRAY);
+        }
+
+        let prompt = "LEFT / RIGHT TO PICK - ENTER TO CLIMB - BACKSPACE TO GO BACK";
+        let prompt_w = d.measure_text(prompt, 24);
+        d.draw_text(prompt, (screen_w / 2.0) as i32 - prompt_w / 2, (screen_h * 0.85) as i32, 24, Color::LIGHTGRAY);
+    }
+}
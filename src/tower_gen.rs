@@ -0,0 +1,339 @@
+//! Seeded tower generator.
+//!
+//! Stacks hand-shaped chunk templates from the starting floor up to the rooftop, jittering,
+//! narrowing and spacing them out more as the climb goes on. Every platform is checked against
+//! the real jump arc before it's kept, so there is always a way up, and the same seed always
+//! builds the same tower. The generator runs on ChaCha8, whose output is fixed, so that holds
+//! across rand versions and platforms too.
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use raylib::prelude::*;
+
+use crate::collision::{move_and_collide, overlaps};
+use crate::level::{BlockDef, HazardDef, HazardType, LevelFile, PlatformDef, PlatformType, RecycleMode, SpawnPoint, PLATFORM_HEIGHT};
+use crate::player::{BODY_HEIGHT, BODY_WIDTH, GRAVITY, JUMP_VELOCITY, MOVE_SPEED};
+use crate::stage::Stage;
+
+pub const TOWER_BOTTOM: f32 = 1000.0;
+pub const ROOFTOP_Y: f32 = -8500.0;
+
+// platforms stay inside this band so nobody has to hug the screen edge
+const PLAYFIELD_LEFT: f32 = 120.0;
+const PLAYFIELD_RIGHT: f32 = 1800.0;
+// rows get further apart with height, but never past what a single jump clears
+const ROW_SPACING_MIN: f32 = 150.0;
+const ROW_SPACING_MAX: f32 = 200.0;
+const WIDTH_MIN: f32 = 220.0;
+const WIDTH_MAX: f32 = 320.0;
+// by the top, platforms are this much narrower
+const WIDTH_SHRINK: f32 = 0.4;
+const X_JITTER: f32 = 80.0;
+// chance of a platform being moving/crumbling/bouncy/ice at the very top
+const SPECIAL_CHANCE: f32 = 0.5;
+// below this height progress everything stays plain
+const CALM_START: f32 = 0.05;
+// the stage hazard sits on one end of wide plain platforms, more often further up
+const HAZARD_CHANCE: f32 = 0.35;
+const HAZARD_MIN_WIDTH: f32 = 180.0;
+const HAZARD_WIDTH: f32 = 70.0;
+const HAZARD_HEIGHT: f32 = 20.0;
+// how many times a chunk is re-rolled before falling back to a plain step
+const MAX_ATTEMPTS: usize = 8;
+// jump simulation resolution
+const SIM_STEP: f32 = 1.0 / 120.0;
+const SIM_TIME: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy)]
+enum BlockShape {
+    // a tall pillar standing between the rows
+    Wall { x: f32 },
+    // a flat slab hanging over the first row
+    Overhang { x: f32 },
+}
+
+// a hand-shaped slice of tower. each row lists platform centres as a fraction of the
+// playfield width, bottom row first
+struct Template {
+    min_difficulty: f32,
+    rows: &'static [&'static [f32]],
+    block: Option<BlockShape>,
+}
+
+const TEMPLATES: &[Template] = &[
+    Template { min_difficulty: 0.0, rows: &[&[0.15, 0.85], &[0.5], &[0.2, 0.8]], block: None },
+    Template { min_difficulty: 0.0, rows: &[&[0.1, 0.5, 0.9], &[0.3, 0.7]], block: None },
+    Template { min_difficulty: 0.0, rows: &[&[0.3], &[0.5], &[0.7]], block: None },
+    Template { min_difficulty: 0.2, rows: &[&[0.25], &[0.75], &[0.3], &[0.7]], block: None },
+    Template { min_difficulty: 0.3, rows: &[&[0.2, 0.8], &[0.35, 0.65]], block: Some(BlockShape::Wall { x: 0.5 }) },
+    Template { min_difficulty: 0.4, rows: &[&[0.3, 0.8], &[0.5]], block: Some(BlockShape::Overhang { x: 0.3 }) },
+    Template { min_difficulty: 0.6, rows: &[&[0.5], &[0.25], &[0.55], &[0.8]], block: None },
+];
+
+/// Builds the whole climb for `seed`, themed and hazarded for `stage`.
+pub fn generate(seed: u64, stage: &Stage) -> LevelFile {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    // wide, calm starting floor under the two spawn points
+    let mut platforms = vec![
+        PlatformDef { x: 350.0, y: TOWER_BOTTOM, width: 500.0, kind: PlatformType::Static, texture: None },
+        PlatformDef { x: 1070.0, y: TOWER_BOTTOM, width: 500.0, kind: PlatformType::Static, texture: None },
+    ];
+    let mut solids = Vec::new();
+    let mut hazards = Vec::new();
+    let mut reachable: Vec<Rectangle> = platforms.iter().map(platform_rect).collect();
+    let mut y = TOWER_BOTTOM;
+
+    // climb right up to the arena floor so there's always somewhere to stand when it appears
+    let floor_y = ROOFTOP_Y + stage.rooftop.floor_offset;
+    while y > floor_y {
+        let difficulty = ((TOWER_BOTTOM - y) / (TOWER_BOTTOM - ROOFTOP_Y)).clamp(0.0, 1.0);
+        let options: Vec<&Template> = TEMPLATES.iter().filter(|t| t.min_difficulty <= difficulty).collect();
+
+        let mut accepted = None;
+        for _ in 0..MAX_ATTEMPTS {
+            let template = options[rng.random_range(0..options.len())];
+            let chunk = build_chunk(template, y, difficulty, stage.hazard, &mut rng);
+            if let Some(reached) = validate(&chunk, &reachable, &solids) {
+                accepted = Some((chunk, reached));
+                break;
+            }
+        }
+        let (chunk, reached) = accepted.unwrap_or_else(|| {
+            let chunk = fallback_chunk(y, &reachable, &mut solids, &mut rng);
+            let reached = chunk.safe.clone();
+            (chunk, reached)
+        });
+
+        y = chunk.top;
+        platforms.extend(chunk.platforms);
+        solids.extend(chunk.solids);
+        hazards.extend(chunk.hazards);
+        reachable.extend(reached);
+    }
+    // ...but nothing poking through it
+    platforms.retain(|p| p.y > floor_y);
+    solids.retain(|b| b.y > floor_y);
+    hazards.retain(|h| h.y > floor_y);
+
+    LevelFile {
+        name: format!("{} #{seed}", stage.name),
+        seed: Some(seed),
+        rooftop_y: ROOFTOP_Y,
+        recycle: RecycleMode::None,
+        tower_height: 0.0,
+        spawn_points: vec![SpawnPoint { x: 600.0, y: TOWER_BOTTOM }, SpawnPoint { x: 1320.0, y: TOWER_BOTTOM }],
+        platforms,
+        solids,
+        hazards,
+        rooftop: stage.rooftop.clone(),
+    }
+}
+
+struct Chunk {
+    platforms: Vec<PlatformDef>,
+    // the part of each platform you can land on without touching a hazard
+    safe: Vec<Rectangle>,
+    solids: Vec<BlockDef>,
+    hazards: Vec<HazardDef>,
+    // y of the highest row, where the next chunk starts from
+    top: f32,
+}
+
+fn build_chunk(template: &Template, base_y: f32, difficulty: f32, hazard: HazardType, rng: &mut ChaCha8Rng) -> Chunk {
+    let span = PLAYFIELD_RIGHT - PLAYFIELD_LEFT;
+    let mut platforms = Vec::new();
+    let mut safe = Vec::new();
+    let mut hazards = Vec::new();
+    let mut y = base_y;
+    let mut first_row_y = base_y;
+
+    for (r, row) in template.rows.iter().enumerate() {
+        let spacing = ROW_SPACING_MIN + (ROW_SPACING_MAX - ROW_SPACING_MIN) * difficulty + rng.random_range(-10.0..10.0);
+        y -= spacing.min(ROW_SPACING_MAX);
+        if r == 0 { first_row_y = y; }
+
+        for &frac in row.iter() {
+            let width = (rng.random_range(WIDTH_MIN..WIDTH_MAX) * (1.0 - WIDTH_SHRINK * difficulty)).round();
+            let center = PLAYFIELD_LEFT + frac * span + rng.random_range(-X_JITTER..X_JITTER);
+            let x = (center - width / 2.0).clamp(PLAYFIELD_LEFT, PLAYFIELD_RIGHT - width).round();
+            let def = PlatformDef { x, y: y.round(), width, kind: pick_kind(difficulty, rng), texture: None };
+
+            // only plain platforms get hazards - anything that moves or falls would leave it floating
+            let mut landing = platform_rect(&def);
+            if def.kind == PlatformType::Static && width >= HAZARD_MIN_WIDTH && difficulty >= CALM_START
+                && rng.random::<f32>() < HAZARD_CHANCE * difficulty {
+                let on_left = rng.random_bool(0.5);
+                let hx = if on_left { def.x } else { def.x + def.width - HAZARD_WIDTH };
+                hazards.push(HazardDef { kind: hazard, x: hx, y: def.y - HAZARD_HEIGHT, width: HAZARD_WIDTH, height: HAZARD_HEIGHT });
+                landing.width -= HAZARD_WIDTH;
+                if on_left { landing.x += HAZARD_WIDTH; }
+            }
+            platforms.push(def);
+            safe.push(landing);
+        }
+    }
+
+    let solids = match template.block {
+        Some(BlockShape::Wall { x }) => vec![BlockDef { x: (PLAYFIELD_LEFT + x * span - 32.0).round(), y: (first_row_y - 240.0).round(), width: 64.0, height: 220.0 }],
+        Some(BlockShape::Overhang { x }) => vec![BlockDef { x: (PLAYFIELD_LEFT + x * span - 128.0).round(), y: (first_row_y - 130.0).round(), width: 256.0, height: 40.0 }],
+        None => Vec::new(),
+    };
+
+    Chunk { platforms, safe, solids, hazards, top: y.round() }
+}
+
+// the odds of a platform doing something special climb with height
+fn pick_kind(difficulty: f32, rng: &mut ChaCha8Rng) -> PlatformType {
+    if difficulty < CALM_START || rng.random::<f32>() >= SPECIAL_CHANCE * difficulty {
+        return PlatformType::Static;
+    }
+    match rng.random_range(0..5) {
+        0 => PlatformType::MovingHorizontal { range: 150.0, speed: 1.5 },
+        1 => PlatformType::MovingVertical { range: 60.0, speed: 1.2 },
+        2 => PlatformType::Crumbling,
+        3 => PlatformType::Bouncy,
+        _ => PlatformType::Ice,
+    }
+}
+
+// every platform in the chunk has to be landable from something already reachable,
+// and no block (old or new) can sit inside a platform. returns the newly reachable landing spots
+fn validate(chunk: &Chunk, reachable: &[Rectangle], solids: &[BlockDef]) -> Option<Vec<Rectangle>> {
+    let blocks: Vec<Rectangle> = solids.iter().chain(chunk.solids.iter()).map(|b| b.build(0.0)).collect();
+    let rects: Vec<Rectangle> = chunk.platforms.iter().map(platform_rect).collect();
+    if blocks.iter().any(|b| rects.iter().any(|r| overlaps(b, r))) {
+        return None;
+    }
+
+    // rows go bottom up, so a platform can also be reached from an earlier one in this chunk
+    let mut known: Vec<Rectangle> = reachable.to_vec();
+    for rect in &chunk.safe {
+        let sources = known.iter().filter(|k| k.y > rect.y && k.y - rect.y <= ROW_SPACING_MAX * 2.0);
+        let mut ok = false;
+        for from in sources {
+            if can_jump(*from, *rect, &blocks) {
+                ok = true;
+                break;
+            }
+        }
+        if !ok { return None; }
+        known.push(*rect);
+    }
+    Some(chunk.safe.clone())
+}
+
+// plain step above something reachable, nudged sideways until it clears the blocks and
+// passes the same check as every other chunk. if even that fails the blocks in the way go -
+// a way up matters more than the scenery
+fn fallback_chunk(base_y: f32, reachable: &[Rectangle], solids: &mut Vec<BlockDef>, rng: &mut ChaCha8Rng) -> Chunk {
+    let top_row = reachable.iter().filter(|r| r.y <= base_y).collect::<Vec<_>>();
+    let from = top_row[rng.random_range(0..top_row.len())];
+    let width = 260.0;
+    let y = base_y - ROW_SPACING_MIN;
+    let step = |offset: f32| {
+        let x = (from.x + from.width / 2.0 - width / 2.0 + offset).clamp(PLAYFIELD_LEFT, PLAYFIELD_RIGHT - width);
+        let def = PlatformDef { x, y, width, kind: PlatformType::Static, texture: None };
+        Chunk {
+            safe: vec![platform_rect(&def)],
+            platforms: vec![def],
+            solids: Vec::new(),
+            hazards: Vec::new(),
+            top: y,
+        }
+    };
+
+    for offset in [0.0, -150.0, 150.0, -300.0, 300.0] {
+        let chunk = step(offset);
+        if validate(&chunk, reachable, solids).is_some() {
+            return chunk;
+        }
+    }
+    let chunk = step(0.0);
+    let column = Rectangle::new(chunk.safe[0].x, y - BODY_HEIGHT * 2.0, width, from.y - y + BODY_HEIGHT * 2.0);
+    solids.retain(|b| !overlaps(&b.build(0.0), &column));
+    chunk
+}
+
+fn platform_rect(p: &PlatformDef) -> Rectangle {
+    Rectangle::new(p.x, p.y, p.width, PLATFORM_HEIGHT)
+}
+
+/// Plays out a full-height jump from `from` to `to` with the game's own movement numbers and
+/// collision, steering towards the target the whole way. Double jumps and dashes aren't used,
+/// so anything that passes is reachable with the basic moveset.
+pub fn can_jump(from: Rectangle, to: Rectangle, solids: &[Rectangle]) -> bool {
+    // the landing span keeps the whole body on the platform
+    let land_lo = to.x + BODY_WIDTH / 2.0 + 5.0;
+    let land_hi = (to.x + to.width - BODY_WIDTH / 2.0 - 5.0).max(land_lo);
+
+    // take off from the spot on `from` closest to where we want to land
+    let takeoff = ((land_lo + land_hi) / 2.0).clamp(from.x + 10.0, from.x + from.width - 10.0);
+    let mut rect = Rectangle::new(takeoff - BODY_WIDTH / 2.0, from.y - BODY_HEIGHT, BODY_WIDTH, BODY_HEIGHT);
+    let mut vel = Vector2::new(0.0, JUMP_VELOCITY);
+
+    for _ in 0..(SIM_TIME / SIM_STEP) as i32 {
+        let center = rect.x + BODY_WIDTH / 2.0;
+        vel.x = ((center.clamp(land_lo, land_hi) - center) / SIM_STEP).clamp(-MOVE_SPEED, MOVE_SPEED);
+        vel.y += GRAVITY * SIM_STEP;
+
+        let moved = move_and_collide(rect, vel, SIM_STEP, solids, &[to], (f32::NEG_INFINITY, f32::INFINITY), |_| false);
+        if moved.ground_platform.is_some() { return true; }
+        rect = moved.rect;
+        vel = moved.vel;
+
+        // fell back past where we started
+        if vel.y > 0.0 && rect.y + BODY_HEIGHT > from.y { return false; }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stage;
+
+    // the part of a platform you can land on, same as `build_chunk` works it out
+    fn landing(level: &LevelFile, p: &PlatformDef) -> Rectangle {
+        let mut rect = platform_rect(p);
+        for h in level.hazards.iter().filter(|h| h.y + h.height == p.y && h.x >= p.x && h.x < p.x + p.width) {
+            rect.width -= h.width;
+            if h.x == p.x { rect.x += h.width; }
+        }
+        rect
+    }
+
+    #[test]
+    fn same_seed_same_tower() {
+        for stage in stage::all() {
+            assert_eq!(generate(42, &stage), generate(42, &stage));
+            assert_ne!(generate(42, &stage).platforms, generate(43, &stage).platforms);
+        }
+    }
+
+    #[test]
+    fn every_platform_can_be_jumped_to_from_below() {
+        for stage in stage::all() {
+            for seed in 0..8 {
+                let level = generate(seed, &stage);
+                let blocks: Vec<Rectangle> = level.solids.iter().map(|b| b.build(0.0)).collect();
+                let spots: Vec<Rectangle> = level.platforms.iter().map(|p| landing(&level, p)).collect();
+                for to in spots.iter().filter(|r| r.y < TOWER_BOTTOM) {
+                    let reachable = spots.iter()
+                        .filter(|from| from.y > to.y && from.y - to.y <= ROW_SPACING_MAX * 2.0)
+                        .any(|from| can_jump(*from, *to, &blocks));
+                    assert!(reachable, "seed {seed} on {}: nothing reaches {to:?}", stage.name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_block_sits_inside_a_platform() {
+        for seed in 0..8 {
+            let level = generate(seed, &stage::courtyard());
+            for b in &level.solids {
+                assert!(level.platforms.iter().all(|p| !overlaps(&b.build(0.0), &platform_rect(p))), "seed {seed}: {b:?}");
+            }
+        }
+    }
+}
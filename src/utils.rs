@@ -42,6 +42,56 @@ pub fn check_collision_circle_rec(center: Vector2, radius: f32, rec: Rectangle)
     return distance <= radius;
 }
 
+/// Sweeps a circle (projectile) along `motion` and finds where it first touches a rectangle.
+/// Returns the fraction of `motion` travelled before contact and the surface normal that was hit.
+/// Starting out already touching counts as a hit at 0.
+/// The rectangle is grown by the radius on every side, so corners are treated as square.
+pub fn sweep_circle_rec(start: Vector2, motion: Vector2, radius: f32, rec: Rectangle) -> Option<(f32, Vector2)> {
+    let min = Vector2::new(rec.x - radius, rec.y - radius);
+    let max = Vector2::new(rec.x + rec.width + radius, rec.y + rec.height + radius);
+
+    // already overlapping (e.g. cast from inside a platform) - that's a hit straight away,
+    // pushing out through whichever side is closest
+    if start.x > min.x && start.x < max.x && start.y > min.y && start.y < max.y {
+        let exits = [
+            (start.x - min.x, Vector2::new(-1.0, 0.0)),
+            (max.x - start.x, Vector2::new(1.0, 0.0)),
+            (start.y - min.y, Vector2::new(0.0, -1.0)),
+            (max.y - start.y, Vector2::new(0.0, 1.0)),
+        ];
+        let (_, normal) = exits.into_iter().min_by(|a, b| a.0.total_cmp(&b.0))?;
+        return Some((0.0, normal));
+    }
+
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vector2::zero();
+
+    // slab test on each axis
+    for (pos, delta, lo, hi, axis_normal) in [
+        (start.x, motion.x, min.x, max.x, Vector2::new(-motion.x.signum(), 0.0)),
+        (start.y, motion.y, min.y, max.y, Vector2::new(0.0, -motion.y.signum())),
+    ] {
+        if delta == 0.0 {
+            if pos < lo || pos > hi { return None; }
+            continue;
+        }
+        let t1 = (lo - pos) / delta;
+        let t2 = (hi - pos) / delta;
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > t_enter {
+            t_enter = near;
+            normal = axis_normal;
+        }
+        t_exit = t_exit.min(far);
+    }
+
+    if !(0.0..=1.0).contains(&t_enter) || t_enter >= t_exit {
+        return None;
+    }
+    Some((t_enter, normal))
+}
+
 pub fn draw_lava_world(
     d: &mut RaylibMode2D<RaylibDrawHandle>,
     lava_tex: &Texture2D,
@@ -70,3 +120,47 @@ pub fn draw_lava_world(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block() -> Rectangle {
+        Rectangle::new(100.0, 0.0, 50.0, 50.0)
+    }
+
+    #[test]
+    fn sweep_hits_head_on() {
+        let (t, normal) = sweep_circle_rec(Vector2::new(0.0, 25.0), Vector2::new(200.0, 0.0), 10.0, block()).unwrap();
+        assert!((t - 0.45).abs() < 1e-5);
+        assert_eq!(normal, Vector2::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn sweep_grazes_past_or_clips_the_edge() {
+        // passes just above the grown box
+        assert!(sweep_circle_rec(Vector2::new(0.0, -11.0), Vector2::new(300.0, 0.0), 10.0, block()).is_none());
+        // clips the top edge on the way past
+        let (t, normal) = sweep_circle_rec(Vector2::new(0.0, -9.0), Vector2::new(300.0, 0.0), 10.0, block()).unwrap();
+        assert!((t - 0.3).abs() < 1e-5);
+        assert_eq!(normal, Vector2::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn sweep_starting_inside_hits_at_zero() {
+        let (t, normal) = sweep_circle_rec(Vector2::new(105.0, 25.0), Vector2::new(200.0, 0.0), 10.0, block()).unwrap();
+        assert_eq!(t, 0.0);
+        assert_eq!(normal, Vector2::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn sweep_without_moving() {
+        assert!(sweep_circle_rec(Vector2::new(0.0, 25.0), Vector2::zero(), 10.0, block()).is_none());
+        assert_eq!(sweep_circle_rec(Vector2::new(125.0, 5.0), Vector2::zero(), 10.0, block()).map(|h| h.0), Some(0.0));
+    }
+
+    #[test]
+    fn sweep_stops_short() {
+        assert!(sweep_circle_rec(Vector2::new(0.0, 25.0), Vector2::new(50.0, 0.0), 10.0, block()).is_none());
+    }
+}
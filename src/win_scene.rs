@@ -1,4 +1,5 @@
 use raylib::prelude::*;
+use crate::canvas::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
 use crate::game_data::GameData;
 use crate::menu_scene::MenuScene;
 use crate::scenes::{Scene, SceneSwitch};
@@ -8,7 +9,7 @@ pub struct WinScene;
 impl Scene for WinScene {
     fn on_enter(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) {
         // just a log to let us know we made it here
-        println!("entered win scene");
+        tracing::debug!("entered win scene");
     }
 
     fn handle_input(&mut self, _rl: &mut RaylibHandle, _data: &mut GameData) -> SceneSwitch {
@@ -31,13 +32,13 @@ impl Scene for WinScene {
     fn draw(&self, d: &mut RaylibDrawHandle, data: &mut GameData) {
         d.clear_background(Color::BLACK);
 
-        let screen_w = d.get_screen_width();
-        let screen_h = d.get_screen_height();
+        let screen_w = VIRTUAL_WIDTH;
+        let screen_h = VIRTUAL_HEIGHT;
 
-        // figure out who hit the 5 point cap
+        // figure out who hit the score cap
         let p1_score = data.player_scores[0];
 
-        let (winner_text, color) = if p1_score >= 5 {
+        let (winner_text, color) = if p1_score >= data.score_to_win {
             ("PLAYER 1 WINS!", Color::BLUE)
         } else {
             ("PLAYER 2 WINS!", Color::RED)
@@ -0,0 +1,263 @@
+#!/usr/bin/env python3
+"""Synthesizes the placeholder music and sound effects under resources/.
+
+Everything is made from plain oscillators and noise so the game has audio that
+matches what `src/audio.rs` and `src/stage.rs` load, until real recordings
+replace it. Run from the repo root:
+
+    python3 tools/synth_audio.py
+
+Output is deterministic (fixed noise seed), so re-running it doesn't churn the files.
+"""
+import math
+import random
+import struct
+import wave
+from pathlib import Path
+
+SFX_RATE = 22050
+MUSIC_RATE = 16000
+
+BPM = 128
+BEAT = 60.0 / BPM
+BARS = 8
+LOOP = BARS * 4 * BEAT
+
+NOTES = {"C": 0, "C#": 1, "D": 2, "D#": 3, "E": 4, "F": 5, "F#": 6, "G": 7, "G#": 8, "A": 9, "A#": 10, "B": 11}
+
+
+def freq(note):
+    """'A4' -> 440.0"""
+    name, octave = note[:-1], int(note[-1])
+    return 440.0 * 2 ** ((NOTES[name] + 12 * (octave - 4) - 9) / 12)
+
+
+# --- oscillators -------------------------------------------------------------
+
+def sine(phase):
+    return math.sin(2 * math.pi * phase)
+
+
+def square(phase):
+    return 1.0 if phase % 1.0 < 0.5 else -1.0
+
+
+def saw(phase):
+    return 2.0 * (phase % 1.0) - 1.0
+
+
+def triangle(phase):
+    p = phase % 1.0
+    return 4.0 * p - 1.0 if p < 0.5 else 3.0 - 4.0 * p
+
+
+def envelope(t, length, attack, release):
+    if t < 0 or t > length:
+        return 0.0
+    if t < attack:
+        return t / attack
+    if t > length - release:
+        return max(0.0, (length - t) / release)
+    return 1.0
+
+
+class Track:
+    def __init__(self, seconds, rate):
+        self.rate = rate
+        self.buf = [0.0] * int(seconds * rate)
+        self.rng = random.Random(7)
+
+    def tone(self, start, length, hz, wave_fn=sine, gain=0.3, attack=0.01, release=0.05, slide=0.0, wrap=True):
+        """Adds a note. `slide` bends the pitch by that many Hz over the note."""
+        n0 = int(start * self.rate)
+        phase = 0.0
+        for i in range(int(length * self.rate)):
+            t = i / self.rate
+            f = hz + slide * t / length
+            phase += f / self.rate
+            idx = n0 + i
+            if wrap:
+                idx %= len(self.buf)
+            elif idx >= len(self.buf):
+                break
+            self.buf[idx] += wave_fn(phase) * gain * envelope(t, length, attack, release)
+
+    def noise(self, start, length, gain=0.3, attack=0.002, release=0.05, smooth=0.0, wrap=True):
+        """Adds a burst of noise. `smooth` (0-1) low-passes it into a rumble."""
+        n0 = int(start * self.rate)
+        last = 0.0
+        for i in range(int(length * self.rate)):
+            t = i / self.rate
+            last = last * smooth + self.rng.uniform(-1, 1) * (1 - smooth)
+            idx = n0 + i
+            if wrap:
+                idx %= len(self.buf)
+            elif idx >= len(self.buf):
+                break
+            self.buf[idx] += last * gain * envelope(t, length, attack, release)
+
+    def lowpass(self, amount):
+        last = 0.0
+        for i, s in enumerate(self.buf):
+            last += (s - last) * amount
+            self.buf[i] = last
+
+    def save(self, path, peak=0.8):
+        top = max(1e-9, max(abs(s) for s in self.buf))
+        scale = peak / top * 32767
+        path = Path(path)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        with wave.open(str(path), "wb") as out:
+            out.setnchannels(1)
+            out.setsampwidth(2)
+            out.setframerate(self.rate)
+            out.writeframes(b"".join(struct.pack("<h", int(s * scale)) for s in self.buf))
+        print(f"wrote {path}")
+
+
+# --- sound effects -----------------------------------------------------------
+
+def sfx(seconds):
+    return Track(seconds, SFX_RATE)
+
+
+def make_sfx(out):
+    cast = sfx(0.35)
+    cast.tone(0, 0.35, 300, saw, gain=0.25, attack=0.01, release=0.2, slide=900, wrap=False)
+    cast.noise(0, 0.3, gain=0.15, release=0.2, smooth=0.6, wrap=False)
+    cast.lowpass(0.35)
+    cast.save(out / "cast.wav")
+
+    hit = sfx(0.3)
+    hit.tone(0, 0.25, 160, sine, gain=0.6, attack=0.002, release=0.2, slide=-110, wrap=False)
+    hit.noise(0, 0.12, gain=0.4, release=0.1, wrap=False)
+    hit.save(out / "hit.wav")
+
+    jump = sfx(0.18)
+    jump.tone(0, 0.18, 420, square, gain=0.2, attack=0.005, release=0.08, slide=380, wrap=False)
+    jump.lowpass(0.3)
+    jump.save(out / "jump.wav")
+
+    land = sfx(0.2)
+    land.noise(0, 0.18, gain=0.5, release=0.15, smooth=0.85, wrap=False)
+    land.tone(0, 0.12, 90, sine, gain=0.4, release=0.1, slide=-40, wrap=False)
+    land.save(out / "land.wav")
+
+    sizzle = sfx(0.7)
+    sizzle.noise(0, 0.7, gain=0.4, attack=0.01, release=0.5, smooth=0.1, wrap=False)
+    for k in range(14):
+        sizzle.noise(0.02 + k * 0.045, 0.01, gain=0.8, attack=0.001, release=0.008, wrap=False)
+    sizzle.save(out / "sizzle.wav")
+
+    victory = sfx(1.6)
+    for k, note in enumerate(["C5", "E5", "G5"]):
+        victory.tone(k * 0.14, 0.16, freq(note), square, gain=0.2, release=0.05, wrap=False)
+    for note in ["C5", "E5", "G5", "C6"]:
+        victory.tone(0.45, 1.1, freq(note), triangle, gain=0.2, attack=0.02, release=0.6, wrap=False)
+    victory.lowpass(0.5)
+    victory.save(out / "victory.wav")
+
+    # the rooftop duel starting
+    stinger = sfx(1.2)
+    stinger.noise(0, 1.2, gain=0.3, attack=0.005, release=1.0, smooth=0.9, wrap=False)
+    for note in ["D3", "A3", "D4", "F4"]:
+        stinger.tone(0, 1.2, freq(note), saw, gain=0.15, attack=0.005, release=0.9, wrap=False)
+    stinger.tone(0, 0.4, 70, sine, gain=0.6, attack=0.002, release=0.3, slide=-30, wrap=False)
+    stinger.lowpass(0.3)
+    stinger.save(out / "stinger.wav")
+
+
+# --- music -------------------------------------------------------------------
+
+def music():
+    return Track(LOOP, MUSIC_RATE)
+
+
+def pad(track, chords, gain=0.08):
+    """One chord per two bars, soft triangles."""
+    for bar in range(BARS):
+        chord = chords[(bar // 2) % len(chords)]
+        for note in chord:
+            track.tone(bar * 4 * BEAT, 4 * BEAT, freq(note), triangle, gain=gain, attack=0.4, release=0.4)
+
+
+def make_menu(out):
+    menu = music()
+    chords = [["A3", "C4", "E4"], ["F3", "A3", "C4"], ["C3", "E3", "G3"], ["G3", "B3", "D4"]]
+    pad(menu, chords, gain=0.1)
+    for bar in range(BARS):
+        chord = chords[(bar // 2) % len(chords)]
+        arp = [chord[0], chord[1], chord[2], chord[1]]
+        for step in range(8):
+            note = arp[step % 4][:-1] + str(int(arp[step % 4][-1]) + 1)
+            menu.tone((bar * 8 + step) * BEAT / 2, BEAT / 2, freq(note), sine, gain=0.12, attack=0.005, release=0.2)
+    menu.lowpass(0.4)
+    menu.save(out / "menu.wav")
+
+
+def kick(track, start, gain=0.7):
+    track.tone(start, 0.25, 110, sine, gain=gain, attack=0.002, release=0.2, slide=-70)
+
+
+def hat(track, start, gain=0.15):
+    track.noise(start, 0.04, gain=gain, attack=0.001, release=0.03)
+
+
+def make_stage(out, name, chords, bass_wave, lead_wave):
+    """Base, drive and peak stems for one stage. All of them share the loop length and
+    chords so they can be faded in and out over each other."""
+    base = music()
+    pad(base, chords)
+    for bar in range(BARS):
+        root = chords[(bar // 2) % len(chords)][0]
+        low = root[:-1] + str(int(root[-1]) - 1)
+        for beat in range(4):
+            base.tone((bar * 4 + beat) * BEAT, BEAT * 0.9, freq(low), bass_wave, gain=0.18, release=0.1)
+    base.lowpass(0.3)
+    base.save(out / f"{name}_base.wav")
+
+    drive = music()
+    for bar in range(BARS):
+        root = chords[(bar // 2) % len(chords)][0]
+        low = root[:-1] + str(int(root[-1]) - 1)
+        for beat in range(4):
+            kick(drive, (bar * 4 + beat) * BEAT)
+            drive.tone((bar * 4 + beat + 0.5) * BEAT, BEAT * 0.4, freq(low), square, gain=0.08, release=0.05)
+    drive.lowpass(0.25)
+    drive.save(out / f"{name}_drive.wav")
+
+    peak = music()
+    for bar in range(BARS):
+        chord = chords[(bar // 2) % len(chords)]
+        up = [n[:-1] + str(int(n[-1]) + 1) for n in chord]
+        arp = [up[0], up[1], up[2], up[1]]
+        for step in range(16):
+            peak.tone((bar * 16 + step) * BEAT / 4, BEAT / 4, freq(arp[step % 4]), lead_wave, gain=0.1, attack=0.003, release=0.06)
+    peak.lowpass(0.35)
+    peak.save(out / f"{name}_peak.wav")
+
+
+def make_tension(out):
+    # unpitched so it sits over either stage's key: ticking hats and a heartbeat
+    tension = music()
+    for bar in range(BARS):
+        for step in range(8):
+            hat(tension, (bar * 8 + step) * BEAT / 2, gain=0.2 if step % 2 else 0.1)
+        for beat in (0, 2):
+            start = (bar * 4 + beat) * BEAT
+            tension.tone(start, 0.15, 60, sine, gain=0.6, attack=0.005, release=0.1)
+            tension.tone(start + 0.2, 0.15, 55, sine, gain=0.45, attack=0.005, release=0.1)
+    tension.save(out / "tension.wav")
+
+
+def main():
+    root = Path(__file__).resolve().parent.parent / "resources"
+    make_sfx(root / "sfx")
+    make_menu(root / "music")
+    make_stage(root / "music", "courtyard", [["A3", "C4", "E4"], ["F3", "A3", "C4"], ["G3", "B3", "D4"], ["E3", "G#3", "B3"]], triangle, sine)
+    make_stage(root / "music", "rooftops", [["D3", "F3", "A3"], ["A#2", "D3", "F3"], ["C3", "E3", "G3"], ["A2", "C#3", "E3"]], saw, square)
+    make_tension(root / "music")
+
+
+if __name__ == "__main__":
+    main()